@@ -0,0 +1,30 @@
+// Animación por cuadros; T puede ser una textura de GPU o el nombre de una imagen
+pub struct Animation<T> {
+    frames: Vec<T>,
+    current_frame: usize,
+    frame_time: f32,
+    timer: f32,
+}
+
+impl<T> Animation<T> {
+    pub fn new(frames: Vec<T>, frame_time: f32) -> Self {
+        Animation {
+            frames,
+            current_frame: 0,
+            frame_time,
+            timer: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.timer += dt;
+        if self.timer >= self.frame_time {
+            self.current_frame = (self.current_frame + 1) % self.frames.len();
+            self.timer = 0.0;
+        }
+    }
+
+    pub fn current(&self) -> &T {
+        &self.frames[self.current_frame]
+    }
+}
@@ -0,0 +1,124 @@
+use raylib::prelude::*;
+use crate::door::Doors;
+use crate::framebuffer::Framebuffer;
+use crate::level::Level;
+use crate::maze::generate::{self, Algorithm};
+use crate::renderer::Renderer;
+use crate::world::{self, World};
+
+const USAGE: &str = "Uso:
+  render <laberinto> <salida.png|salida.bmp> [ancho alto]
+  compare <esperada.png> <obtenida.png>
+  generate <backtracker|prim|kruskal|eller|rooms> [ancho alto [semilla]]";
+
+// Comandos sin ventana; devuelve el código de salida si se pidió alguno
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = args.get(1)?;
+    let result = match command.as_str() {
+        "render" => render(&args[2..]),
+        "compare" => compare(&args[2..]),
+        "generate" => generate(&args[2..]),
+        _ => return None,
+    };
+
+    match result {
+        Ok(code) => Some(code),
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            Some(2)
+        }
+    }
+}
+
+// Dibuja el nivel desde el punto de inicio del jugador y lo guarda en disco
+fn render(args: &[String]) -> Result<i32, String> {
+    let [maze_path, output, rest @ ..] = args else {
+        return Err("Faltan argumentos para render".to_string());
+    };
+    let (width, height) = match rest {
+        [] => (800, 600),
+        [w, h] => (
+            w.parse().map_err(|_| format!("Ancho inválido: {w}"))?,
+            h.parse().map_err(|_| format!("Alto inválido: {h}"))?,
+        ),
+        _ => return Err("Se esperaba ancho y alto".to_string()),
+    };
+
+    let level = Level::load(maze_path).map_err(|e| format!("No se pudo abrir {maze_path}: {e}"))?;
+    let mut textures = world::load_textures()?;
+    for (ch, path) in &level.textures {
+        textures.insert_file(*ch, path)?;
+    }
+    let mut framebuffer = Framebuffer::new(width, height, Color::DARKBLUE);
+
+    let camera = World::from_level(&level).camera();
+    let doors = Doors::new(&level.maze, &level.locks);
+    Renderer::new(20.0).render(&level.maze, &doors, &camera, &textures, &mut framebuffer);
+
+    if output.to_lowercase().ends_with(".bmp") {
+        framebuffer.save_bmp(output)?;
+    } else {
+        framebuffer.save_png(output)?;
+    }
+    Ok(0)
+}
+
+// Compara dos imágenes pixel por pixel (pruebas de imagen de referencia)
+fn compare(args: &[String]) -> Result<i32, String> {
+    let [expected, actual] = args else {
+        return Err("compare necesita dos imágenes".to_string());
+    };
+    let load = |path: &String| {
+        Image::load_image(path)
+            .map(|image| Framebuffer::from_image(&image))
+            .map_err(|e| format!("No se pudo cargar {path}: {e}"))
+    };
+
+    match load(expected)?.diff_count(&load(actual)?) {
+        Some(0) => {
+            println!("Las imágenes son iguales");
+            Ok(0)
+        }
+        Some(n) => {
+            println!("{n} pixeles distintos");
+            Ok(1)
+        }
+        None => {
+            println!("Las imágenes tienen tamaños distintos");
+            Ok(1)
+        }
+    }
+}
+
+// Escribe un laberinto generado en formato de nivel por la salida estándar
+fn generate(args: &[String]) -> Result<i32, String> {
+    let [name, rest @ ..] = args else {
+        return Err("generate necesita un algoritmo".to_string());
+    };
+    let algorithm =
+        Algorithm::from_name(name).ok_or_else(|| format!("Algoritmo desconocido: {name}"))?;
+
+    let mut options = generate::Options::default();
+    match rest {
+        [] => {}
+        [w, h, seed @ ..] if seed.len() <= 1 => {
+            options.width = w.parse().map_err(|_| format!("Ancho inválido: {w}"))?;
+            options.height = h.parse().map_err(|_| format!("Alto inválido: {h}"))?;
+            if let [seed] = seed {
+                options.seed = seed
+                    .parse()
+                    .map_err(|_| format!("Semilla inválida: {seed}"))?;
+            }
+        }
+        _ => return Err("Se esperaba ancho y alto, y opcionalmente la semilla".to_string()),
+    }
+
+    let maze = generate::generate(algorithm, &options);
+    print!(
+        "@name {} {}\n{}",
+        algorithm.name(),
+        options.seed,
+        maze.to_text()
+    );
+    Ok(0)
+}
@@ -0,0 +1,147 @@
+use raylib::prelude::*;
+use crate::door::Doors;
+use crate::maze::Maze;
+
+// Resultado de mover un círculo por la cuadrícula
+pub struct Sweep {
+    pub pos: Vector2,
+    pub normals: Vec<Vector2>, // Normales de las paredes que frenaron el movimiento
+}
+
+// Mueve un círculo de radio `radius` por `motion`, deslizándolo a lo largo de las
+// paredes y las puertas cerradas. El movimiento se parte en tramos más cortos que
+// el radio para que el círculo no atraviese paredes delgadas a alta velocidad.
+pub fn slide(maze: &Maze, doors: &Doors, pos: Vector2, radius: f32, motion: Vector2) -> Sweep {
+    let steps = (motion.length() / (radius * 0.5)).ceil().max(1.0) as usize;
+    let step = motion / steps as f32;
+
+    let mut sweep = Sweep {
+        pos,
+        normals: Vec::new(),
+    };
+    for _ in 0..steps {
+        sweep.pos += step;
+        // Resolver varias veces: salir de una pared puede meter al círculo en otra
+        for _ in 0..4 {
+            let Some(normal) = push_out(maze, doors, &mut sweep.pos, radius) else { break };
+            if !sweep.normals.contains(&normal) {
+                sweep.normals.push(normal);
+            }
+        }
+    }
+    sweep
+}
+
+// Saca el círculo de la pared con la que más se superpone; devuelve la normal usada
+fn push_out(maze: &Maze, doors: &Doors, pos: &mut Vector2, radius: f32) -> Option<Vector2> {
+    let (normal, depth) = wall_cells(maze, doors, *pos, radius)
+        .filter_map(|cell| penetration(cell, *pos, radius))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    *pos += normal * depth;
+    Some(normal)
+}
+
+// Celdas con pared que toca la caja que encierra al círculo
+fn wall_cells<'a>(
+    maze: &'a Maze,
+    doors: &'a Doors,
+    pos: Vector2,
+    radius: f32,
+) -> impl Iterator<Item = (i32, i32)> + 'a {
+    let span = |center: f32| (center - radius).floor() as i32..=(center + radius).floor() as i32;
+    let columns = span(pos.x);
+    span(pos.y)
+        .flat_map(move |y| columns.clone().map(move |x| (x, y)))
+        .filter(|&(x, y)| doors.blocks(maze, x, y))
+}
+
+// Normal y profundidad de la superposición entre el círculo y la celda (x, y)
+fn penetration((x, y): (i32, i32), pos: Vector2, radius: f32) -> Option<(Vector2, f32)> {
+    let (left, top) = (x as f32, y as f32);
+    let closest = Vector2::new(pos.x.clamp(left, left + 1.0), pos.y.clamp(top, top + 1.0));
+    let offset = pos - closest;
+    let distance = offset.length();
+
+    if distance > 0.0 {
+        return (distance < radius).then(|| (offset / distance, radius - distance));
+    }
+
+    // El centro quedó dentro de la celda: salir por el lado más cercano
+    let exits = [
+        (Vector2::new(-1.0, 0.0), pos.x - left),
+        (Vector2::new(1.0, 0.0), left + 1.0 - pos.x),
+        (Vector2::new(0.0, -1.0), pos.y - top),
+        (Vector2::new(0.0, 1.0), top + 1.0 - pos.y),
+    ];
+    exits
+        .into_iter()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(normal, gap)| (normal, gap + radius))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::Layer;
+
+    const RADIUS: f32 = 0.2;
+
+    fn maze(rows: &[&str]) -> Maze {
+        let grid = rows.iter().map(|row| row.chars().collect()).collect();
+        Maze::new(grid, Layer::default(), Layer::default())
+    }
+
+    fn slide_free(maze: &Maze, start: Vector2, motion: Vector2) -> Sweep {
+        slide(maze, &Doors::default(), start, RADIUS, motion)
+    }
+
+    // Cuánto se mete el círculo en la pared más cercana; 0 si no la toca
+    fn overlap(maze: &Maze, pos: Vector2) -> f32 {
+        wall_cells(maze, &Doors::default(), pos, RADIUS)
+            .filter_map(|cell| penetration(cell, pos, RADIUS))
+            .map(|(_, depth)| depth)
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn diagonal_into_a_wall_keeps_the_tangent() {
+        let maze = maze(&["#####", "#   #", "#   #", "#####"]);
+        let start = Vector2::new(1.5, 1.5);
+        let sweep = slide_free(&maze, start, Vector2::new(0.5, -0.5));
+        // Arriba hay pared: se frena en y pero sigue todo lo que pedía en x
+        assert!((sweep.pos.x - 2.0).abs() < 1e-4);
+        assert!((sweep.pos.y - (1.0 + RADIUS)).abs() < 1e-4);
+        assert_eq!(sweep.normals, vec![Vector2::new(0.0, 1.0)]);
+    }
+
+    #[test]
+    fn long_moves_do_not_tunnel_through_thin_walls() {
+        let maze = maze(&["#######", "#  #  #", "#######"]);
+        let start = Vector2::new(1.5, 1.5);
+        let sweep = slide_free(&maze, start, Vector2::new(4.0, 0.0));
+        assert!(sweep.pos.x <= 3.0 - RADIUS + 1e-4);
+        assert_eq!(sweep.normals, vec![Vector2::new(-1.0, 0.0)]);
+    }
+
+    #[test]
+    fn never_ends_inside_a_wall() {
+        let maze = maze(&["######", "#    #", "# #  #", "#    #", "######"]);
+        let start = Vector2::new(1.5, 1.5);
+        for i in 0..32 {
+            let angle = i as f32 * std::f32::consts::TAU / 32.0;
+            let motion = Vector2::new(angle.cos(), angle.sin()) * 3.0;
+            let pos = slide_free(&maze, start, motion).pos;
+            // Queda apoyado en la pared sin meterse
+            assert!(overlap(&maze, pos) < 1e-3, "ángulo {angle}: {pos:?}");
+        }
+    }
+
+    #[test]
+    fn closed_doors_block_like_walls() {
+        let maze = maze(&["#####", "#   #", "##D##", "#   #", "#####"]);
+        let doors = Doors::new(&maze, &[]);
+        let start = Vector2::new(2.5, 1.5);
+        let sweep = slide(&maze, &doors, start, RADIUS, Vector2::new(0.0, 2.0));
+        assert!(sweep.pos.y <= 2.0 - RADIUS + 1e-4);
+    }
+}
@@ -0,0 +1,260 @@
+use raylib::prelude::*;
+use crate::item::KeyColor;
+use crate::maze::{Maze, DOOR_TILE};
+use crate::pathfinding::Cell;
+use crate::textures::{TextureImage, TextureSet};
+
+const OPEN_SPEED: f32 = 1.5; // Fracción de la hoja por segundo
+const CLOSE_DELAY: f32 = 4.0; // Segundos abierta antes de cerrarse sola
+const USE_DISTANCE: f32 = 1.2; // Alcance del jugador, en celdas
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoorState {
+    Closed,
+    Opening,
+    Open,
+    Closing,
+}
+
+impl DoorState {
+    pub const ALL: [DoorState; 4] = [
+        DoorState::Closed,
+        DoorState::Opening,
+        DoorState::Open,
+        DoorState::Closing,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DoorState::Closed => "closed",
+            DoorState::Opening => "opening",
+            DoorState::Open => "open",
+            DoorState::Closing => "closing",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<DoorState> {
+        DoorState::ALL.into_iter().find(|s| s.name() == name)
+    }
+}
+
+pub struct Door {
+    pub cell: Cell,
+    pub across_x: bool, // La hoja está en x = centro de la celda (ver Maze::door_frame)
+    pub open: f32,      // 0 = cerrada, 1 = abierta del todo
+    pub state: DoorState,
+    pub key: Option<KeyColor>, // Llave necesaria para abrirla
+    timer: f32,
+}
+
+impl Door {
+    // Mientras se mueve sigue bloqueando el paso
+    pub fn blocks(&self) -> bool {
+        self.open < 1.0
+    }
+}
+
+// Qué pasó al intentar usar una puerta
+pub enum DoorUse {
+    Opened,
+    Closed,
+    Locked(KeyColor),
+}
+
+// Estado de las puertas del nivel en juego; la cuadrícula del Maze no cambia
+#[derive(Default)]
+pub struct Doors {
+    doors: Vec<Door>,
+}
+
+impl Doors {
+    // Todas cerradas; `locks` indica qué llave pide cada puerta
+    pub fn new(maze: &Maze, locks: &[(Cell, KeyColor)]) -> Self {
+        let mut doors = Vec::new();
+        for y in 0..maze.height() {
+            for x in 0..maze.width() {
+                if maze.get_cell(x as i32, y as i32) != Some(DOOR_TILE) {
+                    continue;
+                }
+                let key = locks
+                    .iter()
+                    .find(|(cell, _)| *cell == (x, y))
+                    .map(|&(_, key)| key);
+                doors.push(Door {
+                    cell: (x, y),
+                    across_x: maze.door_frame((x, y)).unwrap_or(true),
+                    open: 0.0,
+                    state: DoorState::Closed,
+                    key,
+                    timer: 0.0,
+                });
+            }
+        }
+        Doors { doors }
+    }
+
+    pub fn get(&self, cell: Cell) -> Option<&Door> {
+        self.doors.iter().find(|door| door.cell == cell)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Door> {
+        self.doors.iter()
+    }
+
+    // Deja la puerta de `cell` como estaba al guardar la partida
+    pub fn restore(&mut self, cell: Cell, open: f32, state: DoorState) {
+        if let Some(door) = self.doors.iter_mut().find(|door| door.cell == cell) {
+            door.open = open.clamp(0.0, 1.0);
+            door.state = state;
+            door.timer = CLOSE_DELAY;
+        }
+    }
+
+    // Abre la puerta de `cell` sin pedir llave (eventos del nivel)
+    pub fn open(&mut self, cell: Cell) {
+        if let Some(door) = self.doors.iter_mut().find(|door| door.cell == cell) {
+            if matches!(door.state, DoorState::Closed | DoorState::Closing) {
+                door.state = DoorState::Opening;
+            }
+        }
+    }
+
+    // Pared sólida en (x, y): paredes comunes y puertas que no están abiertas del todo
+    pub fn blocks(&self, maze: &Maze, x: i32, y: i32) -> bool {
+        if maze.get_cell(x, y).is_none() {
+            return false;
+        }
+        if x < 0 || y < 0 {
+            return true;
+        }
+        self.get((x as usize, y as usize)).is_none_or(Door::blocks)
+    }
+
+    // Abre o cierra la puerta que está frente a `pos` mirando hacia `angle`
+    pub fn use_door(
+        &mut self,
+        pos: Vector2,
+        angle: f32,
+        has_key: impl Fn(KeyColor) -> bool,
+    ) -> Option<DoorUse> {
+        let (dx, dy) = (angle.cos(), angle.sin());
+        let door = [0.5, USE_DISTANCE].into_iter().find_map(|reach| {
+            let (x, y) = (pos.x + dx * reach, pos.y + dy * reach);
+            if x < 0.0 || y < 0.0 {
+                return None;
+            }
+            let cell = (x as usize, y as usize);
+            self.doors.iter().position(|door| door.cell == cell)
+        })?;
+        let door = &mut self.doors[door];
+
+        if let Some(key) = door.key {
+            if !has_key(key) {
+                return Some(DoorUse::Locked(key));
+            }
+        }
+        match door.state {
+            DoorState::Closed | DoorState::Closing => {
+                door.state = DoorState::Opening;
+                Some(DoorUse::Opened)
+            }
+            DoorState::Open | DoorState::Opening => {
+                door.state = DoorState::Closing;
+                Some(DoorUse::Closed)
+            }
+        }
+    }
+
+    // Avanza las hojas; una puerta no se cierra sobre ninguno de los círculos
+    // (posición, radio) de `bodies`
+    pub fn update(&mut self, dt: f32, bodies: &[(Vector2, f32)]) {
+        for door in &mut self.doors {
+            let (x, y) = (door.cell.0 as f32, door.cell.1 as f32);
+            let occupied = bodies.iter().any(|&(pos, radius)| {
+                pos.x + radius > x
+                    && pos.x - radius < x + 1.0
+                    && pos.y + radius > y
+                    && pos.y - radius < y + 1.0
+            });
+
+            match door.state {
+                DoorState::Closed => {}
+                DoorState::Opening => {
+                    door.open = (door.open + OPEN_SPEED * dt).min(1.0);
+                    if door.open >= 1.0 {
+                        door.state = DoorState::Open;
+                        door.timer = CLOSE_DELAY;
+                    }
+                }
+                DoorState::Open => {
+                    door.timer -= dt;
+                    if door.timer <= 0.0 && !occupied {
+                        door.state = DoorState::Closing;
+                    }
+                }
+                DoorState::Closing if occupied => door.state = DoorState::Opening,
+                DoorState::Closing => {
+                    door.open = (door.open - OPEN_SPEED * dt).max(0.0);
+                    if door.open <= 0.0 {
+                        door.state = DoorState::Closed;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// La hoja se dibuja como una pared más, con la letra de la puerta
+pub fn load_textures(set: &mut TextureSet) {
+    set.insert(DOOR_TILE, door_image());
+}
+
+// Puerta de tablas con marco y manija, generada sin archivo
+fn door_image() -> TextureImage {
+    let size = 64;
+    TextureImage::generate(size, |x, y| {
+        let frame = x < 4 || x >= size - 4 || y < 4 || y >= size - 4;
+        let handle = (50..55).contains(&x) && (29..35).contains(&y);
+        if handle {
+            Color::GOLD
+        } else if frame {
+            Color::new(70, 70, 80, 255)
+        } else if x % 14 == 4 {
+            Color::new(60, 35, 15, 255) // Junta entre tablas
+        } else {
+            // Veta de la madera
+            let grain = ((x * 7 + y / 3 * 13) % 17) as u8;
+            Color::new(120 + grain, 75 + grain / 2, 35, 255)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::Layer;
+
+    #[test]
+    fn does_not_close_on_any_body() {
+        let rows = ["#####", "#   #", "##D##", "#   #", "#####"];
+        let grid = rows.iter().map(|row| row.chars().collect()).collect();
+        let maze = Maze::new(grid, Layer::default(), Layer::default());
+        let mut doors = Doors::new(&maze, &[]);
+        doors.open((2, 2));
+
+        // El jugador lejos y un enemigo parado en la puerta
+        let player = (Vector2::new(1.5, 1.5), 0.2);
+        let enemy = (Vector2::new(2.5, 2.5), 0.25);
+        for _ in 0..600 {
+            doors.update(0.05, &[player, enemy]);
+            assert_ne!(doors.get((2, 2)).unwrap().state, DoorState::Closed);
+        }
+        assert!(doors.get((2, 2)).unwrap().open > 0.99);
+
+        // Cuando se va, se cierra
+        for _ in 0..600 {
+            doors.update(0.05, &[player]);
+        }
+        assert_eq!(doors.get((2, 2)).unwrap().state, DoorState::Closed);
+    }
+}
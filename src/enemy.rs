@@ -0,0 +1,433 @@
+use raylib::prelude::*;
+use std::f32::consts::PI;
+use crate::animation::Animation;
+use crate::door::Doors;
+use crate::maze::Maze;
+use crate::pathfinding::{self, Cell};
+use crate::raycaster;
+use crate::sprite::Sprite;
+use crate::textures::{TextureImage, TextureSet};
+use crate::world::Transform;
+
+pub const RADIUS: f32 = 0.25; // Radio de colisión, en celdas
+const CONTACT_DISTANCE: f32 = 0.45; // Distancia a la que alcanza al jugador
+const REPATH_TIME: f32 = 0.5; // Segundos entre búsquedas de camino
+const SEARCH_TIME: f32 = 4.0; // Segundos buscando antes de volver a patrullar
+const ATTACK_TIME: f32 = 1.0; // Segundos entre golpes mientras lo sigue tocando
+const ARRIVE_DISTANCE: f32 = 0.1;
+pub const HIT_RADIUS: f32 = 0.3; // Ancho del blanco para los disparos, en celdas
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnemyKind {
+    Guard,
+    Hound,
+}
+
+impl EnemyKind {
+    pub const ALL: [EnemyKind; 2] = [EnemyKind::Guard, EnemyKind::Hound];
+
+    // Nombre en los archivos de nivel
+    pub fn name(self) -> &'static str {
+        match self {
+            EnemyKind::Guard => "guard",
+            EnemyKind::Hound => "hound",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<EnemyKind> {
+        EnemyKind::ALL.into_iter().find(|k| k.name() == name)
+    }
+
+    // Nombre en pantalla
+    pub fn label(self) -> &'static str {
+        match self {
+            EnemyKind::Guard => "un guardia",
+            EnemyKind::Hound => "un sabueso",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            EnemyKind::Guard => Color::new(50, 100, 50, 255),
+            EnemyKind::Hound => Color::new(120, 70, 30, 255),
+        }
+    }
+
+    // Imágenes de la caminata
+    pub fn frames(self) -> [&'static str; 2] {
+        match self {
+            EnemyKind::Guard => ["guard1", "guard2"],
+            EnemyKind::Hound => ["hound1", "hound2"],
+        }
+    }
+
+    // Imagen del enemigo derribado
+    pub fn corpse(self) -> &'static str {
+        match self {
+            EnemyKind::Guard => "guard_dead",
+            EnemyKind::Hound => "hound_dead",
+        }
+    }
+
+    // Caminando, o tirado en el piso si ya lo derribaron
+    pub fn sprite(self, dead: bool) -> Sprite {
+        let animation = if dead {
+            Animation::new(vec![self.corpse()], 1.0)
+        } else {
+            Animation::new(self.frames().to_vec(), 0.25)
+        };
+        Sprite::new(self.scale(), animation)
+    }
+
+    // Salud al aparecer
+    pub fn health(self) -> f32 {
+        match self {
+            EnemyKind::Guard => 60.0,
+            EnemyKind::Hound => 30.0,
+        }
+    }
+
+    // Puntos por derribarlo
+    pub fn score(self) -> u32 {
+        match self {
+            EnemyKind::Guard => 100,
+            EnemyKind::Hound => 50,
+        }
+    }
+
+    // Celdas por segundo patrullando y persiguiendo
+    fn speed(self, state: EnemyState) -> f32 {
+        match (self, state) {
+            (EnemyKind::Guard, EnemyState::Patrol) => 1.2,
+            (EnemyKind::Guard, _) => 2.5,
+            (EnemyKind::Hound, EnemyState::Patrol) => 1.8,
+            (EnemyKind::Hound, _) => 3.3,
+        }
+    }
+
+    // Alcance de la vista en celdas y medio ángulo del cono de visión.
+    // El sabueso ve poco pero huele en todas direcciones.
+    fn sight(self) -> (f32, f32) {
+        match self {
+            EnemyKind::Guard => (8.0, PI / 4.0),
+            EnemyKind::Hound => (5.0, PI),
+        }
+    }
+
+    // Daño de cada golpe
+    fn damage(self) -> f32 {
+        match self {
+            EnemyKind::Guard => 25.0,
+            EnemyKind::Hound => 15.0,
+        }
+    }
+
+    fn scale(self) -> f32 {
+        match self {
+            EnemyKind::Guard => 0.8,
+            EnemyKind::Hound => 0.45,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnemyState {
+    Patrol, // Recorre sus puntos de ruta
+    Chase,  // Ve al jugador y va por él
+    Search, // Lo perdió de vista: va a donde lo vio por última vez
+}
+
+// Componente de IA: patrulla, persigue y busca moviendo la entidad que lo lleva
+pub struct Enemy {
+    pub kind: EnemyKind,
+    pub state: EnemyState,
+    pub health: f32,     // En 0 queda tirado en el piso
+    route: Vec<Vector2>, // Puntos de patrulla; con uno solo se queda de guardia
+    next_point: usize,
+    path: Vec<Cell>, // Camino en la cuadrícula hasta el objetivo actual
+    // Última búsqueda que no encontró camino: (desde, hasta)
+    no_path: Option<(Cell, Cell)>,
+    repath_timer: f32,
+    last_seen: Vector2,
+    search_timer: f32,
+    attack_timer: f32,
+}
+
+impl Enemy {
+    // `route` empieza en la celda donde aparece
+    pub fn new(kind: EnemyKind, route: &[Cell]) -> Self {
+        let route: Vec<Vector2> = route
+            .iter()
+            .map(|&(x, y)| Vector2::new(x as f32 + 0.5, y as f32 + 0.5))
+            .collect();
+        Enemy {
+            kind,
+            state: EnemyState::Patrol,
+            health: kind.health(),
+            next_point: 1 % route.len().max(1),
+            last_seen: route.first().copied().unwrap_or_default(),
+            route,
+            path: Vec::new(),
+            no_path: None,
+            repath_timer: 0.0,
+            search_timer: 0.0,
+            attack_timer: 0.0,
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health <= 0.0
+    }
+
+    // Deja al enemigo como estaba al guardar la partida, de nuevo patrullando
+    pub fn restore(&mut self, health: f32) {
+        self.state = EnemyState::Patrol;
+        self.path.clear();
+        self.health = health;
+    }
+
+    // Un disparo desde `from`; aunque no lo haya visto, sale a buscar al tirador.
+    // Devuelve true si lo derribó.
+    pub fn hurt(&mut self, amount: f32, from: Vector2) -> bool {
+        if self.is_dead() {
+            return false;
+        }
+        self.health = (self.health - amount).max(0.0);
+        if self.is_dead() {
+            return true;
+        }
+        if self.state == EnemyState::Patrol {
+            self.state = EnemyState::Search;
+            self.path.clear();
+        }
+        self.last_seen = from;
+        self.search_timer = SEARCH_TIME;
+        false
+    }
+
+    // Un paso de simulación: elige hacia dónde caminar dejando la velocidad en
+    // `body` (el movimiento lo hace el sistema de colisiones, que no sabe abrir
+    // puertas) y devuelve el daño si está tocando al jugador
+    pub fn update(
+        &mut self,
+        body: &mut Transform,
+        maze: &Maze,
+        doors: &Doors,
+        player: Vector2,
+        dt: f32,
+    ) -> Option<f32> {
+        body.velocity = Vector2::zero();
+        if self.is_dead() {
+            return None;
+        }
+        let pos = body.pos;
+        if self.sees(body, maze, doors, player) {
+            if self.state == EnemyState::Patrol {
+                self.path.clear();
+            }
+            self.state = EnemyState::Chase;
+            self.last_seen = player;
+            self.search_timer = SEARCH_TIME;
+        } else if self.state == EnemyState::Chase {
+            self.state = EnemyState::Search;
+        }
+
+        let target = match self.state {
+            EnemyState::Patrol => self.route.get(self.next_point).copied().unwrap_or(pos),
+            EnemyState::Chase | EnemyState::Search => self.last_seen,
+        };
+        let arrived = pos.distance_to(target) < ARRIVE_DISTANCE;
+        match self.state {
+            EnemyState::Patrol if arrived && !self.route.is_empty() => {
+                self.next_point = (self.next_point + 1) % self.route.len();
+                self.path.clear();
+            }
+            EnemyState::Search => {
+                self.search_timer -= dt;
+                if arrived || self.search_timer <= 0.0 {
+                    self.state = EnemyState::Patrol;
+                    self.path.clear();
+                }
+            }
+            _ => {}
+        }
+
+        if !arrived {
+            let step = self.steer(maze, doors, pos, target, dt);
+            body.velocity = step * self.kind.speed(self.state);
+            if step.length() > 0.0 {
+                body.angle = step.y.atan2(step.x);
+            }
+        }
+
+        self.attack_timer = (self.attack_timer - dt).max(0.0);
+        if self.attack_timer > 0.0 || pos.distance_to(player) >= CONTACT_DISTANCE {
+            return None;
+        }
+        self.attack_timer = ATTACK_TIME;
+        Some(self.kind.damage())
+    }
+
+    // Dirección hacia el centro de la próxima celda del camino, o directo al
+    // objetivo cuando ya está en su celda. No sabe abrir puertas: el camino las
+    // rodea, y si no hay otro paso se queda esperando a que alguien las abra.
+    fn steer(
+        &mut self,
+        maze: &Maze,
+        doors: &Doors,
+        pos: Vector2,
+        target: Vector2,
+        dt: f32,
+    ) -> Vector2 {
+        let here = cell_of(pos);
+        let goal = cell_of(target);
+
+        // Se vuelve a buscar cada tanto, o antes si se salió del camino o el objetivo
+        // cambió; una búsqueda que ya falló no se repite hasta que pase el tiempo
+        self.repath_timer -= dt;
+        let stale = (!self.path.contains(&here) || self.path.last() != Some(&goal))
+            && self.no_path != Some((here, goal));
+        if self.repath_timer <= 0.0 || stale {
+            let closed = |(x, y): Cell| doors.blocks(maze, x as i32, y as i32);
+            let path = pathfinding::find_path(maze, here, goal, closed);
+            self.no_path = path.is_none().then_some((here, goal));
+            self.path = path.unwrap_or_default();
+            self.repath_timer = REPATH_TIME;
+        }
+
+        let next = self
+            .path
+            .iter()
+            .position(|&cell| cell == here)
+            .and_then(|i| self.path.get(i + 1));
+        let point = match next {
+            Some(&(x, y)) => Vector2::new(x as f32 + 0.5, y as f32 + 0.5),
+            None if here != goal => return Vector2::zero(),
+            None => target,
+        };
+        let offset = point - pos;
+        let length = offset.length();
+        if length > 0.0 {
+            offset / length
+        } else {
+            Vector2::zero()
+        }
+    }
+
+    // Patrullando solo mira hacia adelante; alerta, mira a todos lados
+    fn sees(&self, body: &Transform, maze: &Maze, doors: &Doors, player: Vector2) -> bool {
+        let (range, half_angle) = self.kind.sight();
+        let offset = player - body.pos;
+        let relative = offset.y.atan2(offset.x) - body.angle;
+        let relative = (relative + PI).rem_euclid(2.0 * PI) - PI;
+        if self.state == EnemyState::Patrol && relative.abs() > half_angle {
+            return false;
+        }
+        can_see(maze, doors, body.pos, player, range)
+    }
+}
+
+// Línea de visión: ninguna pared ni puerta cerrada entre `from` y `to`
+pub fn can_see(maze: &Maze, doors: &Doors, from: Vector2, to: Vector2, range: f32) -> bool {
+    let distance = from.distance_to(to);
+    if distance > range {
+        return false;
+    }
+    let angle = (to.y - from.y).atan2(to.x - from.x);
+    raycaster::cast_ray(maze, doors, from, angle, distance).is_none()
+}
+
+fn cell_of(pos: Vector2) -> Cell {
+    (pos.x.max(0.0) as usize, pos.y.max(0.0) as usize)
+}
+
+// Dos pasos de la caminata y el cuerpo caído de cada tipo de enemigo
+pub fn load_textures(set: &mut TextureSet) {
+    for kind in EnemyKind::ALL {
+        let [first, second] = kind.frames();
+        set.insert_sprite(first, figure_image(kind.color(), false));
+        set.insert_sprite(second, figure_image(kind.color(), true));
+        set.insert_sprite(kind.corpse(), corpse_image(kind.color()));
+    }
+}
+
+// Figura de frente: cabeza, cuerpo del color del enemigo y piernas que se
+// alternan con `stride`; fondo transparente
+fn figure_image(body: Color, stride: bool) -> TextureImage {
+    TextureImage::generate(32, |x, y| {
+        let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+        let head = ((x - 16.0).powi(2) + (y - 6.0).powi(2)).sqrt() < 5.0;
+        let eye = (y - 5.5).abs() < 1.0 && ((x - 14.0).abs() < 1.0 || (x - 18.0).abs() < 1.0);
+        let torso = (9.0..23.0).contains(&x) && (11.0..23.0).contains(&y);
+        let (left, right) = if stride { (1.0, -1.0) } else { (-1.0, 1.0) };
+        let legs = (23.0..32.0).contains(&y)
+            && ((x - 12.5 - left).abs() < 2.5 || (x - 19.5 - right).abs() < 2.5);
+        if eye {
+            Color::RED
+        } else if head {
+            Color::new(200, 170, 140, 255)
+        } else if torso {
+            body
+        } else if legs {
+            Color::new(40, 40, 40, 255)
+        } else {
+            Color::new(0, 0, 0, 0)
+        }
+    })
+}
+
+// Enemigo caído: el cuerpo acostado sobre un charco; fondo transparente
+fn corpse_image(body: Color) -> TextureImage {
+    TextureImage::generate(32, |x, y| {
+        let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+        let head = ((x - 7.0).powi(2) + (y - 27.0).powi(2)).sqrt() < 4.0;
+        let torso = (11.0..27.0).contains(&x) && (24.0..30.0).contains(&y);
+        let puddle = ((x - 16.0) / 14.0).powi(2) + ((y - 30.0) / 2.5).powi(2) < 1.0;
+        if head {
+            Color::new(200, 170, 140, 255)
+        } else if torso {
+            body
+        } else if puddle {
+            Color::new(120, 0, 0, 255)
+        } else {
+            Color::new(0, 0, 0, 0)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::Layer;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn waits_at_closed_doors() {
+        let rows = ["#####", "#   #", "##D##", "#   #", "#####"];
+        let grid = rows.iter().map(|row| row.chars().collect()).collect();
+        let maze = Maze::new(grid, Layer::default(), Layer::default());
+        let mut doors = Doors::new(&maze, &[]);
+        let mut enemy = Enemy::new(EnemyKind::Guard, &[(3, 1), (2, 3)]);
+        let mut body = Transform::new(Vector2::new(3.5, 1.5), 0.0);
+        // A espaldas del guardia: no lo ve
+        let player = Vector2::new(1.5, 1.5);
+
+        // La única salida es la puerta cerrada: no empuja contra ella
+        for _ in 0..60 {
+            enemy.update(&mut body, &maze, &doors, player, DT);
+            assert_eq!(body.velocity, Vector2::zero());
+        }
+
+        doors.open((2, 2));
+        for _ in 0..120 {
+            doors.update(DT, &[(body.pos, RADIUS)]);
+        }
+        let mut moved = false;
+        for _ in 0..60 {
+            enemy.update(&mut body, &maze, &doors, player, DT);
+            moved |= body.velocity.length() > 0.0;
+        }
+        assert!(moved);
+    }
+}
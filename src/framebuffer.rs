@@ -1,10 +1,15 @@
 use raylib::prelude::*;
+use std::ffi::CString;
+use std::fs;
+use std::path::Path;
+use std::slice;
 
 pub struct Framebuffer {
     width: usize,
     height: usize,
     pixels: Vec<Color>,
     background: Color,
+    texture: Option<Texture2D>, // Copia en GPU, se crea al subir el primer cuadro
 }
 
 impl Framebuffer {
@@ -15,9 +20,29 @@ impl Framebuffer {
             height,
             pixels,
             background,
+            texture: None,
         }
     }
 
+    // Copia los pixeles de una imagen (p. ej. una captura cargada desde PNG)
+    pub fn from_image(image: &Image) -> Self {
+        Framebuffer {
+            width: image.width as usize,
+            height: image.height as usize,
+            pixels: image.get_image_data().to_vec(),
+            background: Color::BLACK,
+            texture: None,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
     pub fn clear(&mut self) {
         for pixel in &mut self.pixels {
             *pixel = self.background;
@@ -30,12 +55,170 @@ impl Framebuffer {
         }
     }
 
-    pub fn draw(&self, d: &mut RaylibDrawHandle) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let color = self.pixels[y * self.width + x];
-                d.draw_pixel(x as i32, y as i32, color);
+    // Mezcla todo el cuadro con `color`; `amount` va de 0 (nada) a 1 (color liso)
+    pub fn flash(&mut self, color: Color, amount: f32) {
+        let amount = amount.clamp(0.0, 1.0);
+        let mix = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * amount) as u8;
+        for pixel in &mut self.pixels {
+            pixel.r = mix(pixel.r, color.r);
+            pixel.g = mix(pixel.g, color.g);
+            pixel.b = mix(pixel.b, color.b);
+        }
+    }
+
+    // Cantidad de pixeles distintos entre dos framebuffers; None si no miden lo mismo
+    pub fn diff_count(&self, other: &Framebuffer) -> Option<usize> {
+        if self.width != other.width || self.height != other.height {
+            return None;
+        }
+        Some(
+            self.pixels
+                .iter()
+                .zip(&other.pixels)
+                .filter(|(a, b)| a != b)
+                .count(),
+        )
+    }
+
+    pub fn to_image(&self) -> Image {
+        let image = Image::gen_image_color(self.width as i32, self.height as i32, self.background);
+
+        // GenImageColor crea la imagen en formato R8G8B8A8, igual que Color
+        unsafe {
+            let data = slice::from_raw_parts_mut(image.data as *mut Color, self.pixels.len());
+            data.copy_from_slice(&self.pixels);
+        }
+        image
+    }
+
+    pub fn save_png(&self, path: &str) -> Result<(), String> {
+        if !path.to_lowercase().ends_with(".png") {
+            return Err(format!("{path} no tiene extensión .png"));
+        }
+        let image = self.to_image();
+        let c_path = CString::new(path).map_err(|e| e.to_string())?;
+        let ok = unsafe { raylib::ffi::ExportImage(*image, c_path.as_ptr()) };
+        if ok {
+            Ok(())
+        } else {
+            Err(format!("No se pudo escribir {path}"))
+        }
+    }
+
+    // BMP de 24 bits escrito a mano: raylib no trae el exportador BMP en Linux
+    pub fn save_bmp(&self, path: &str) -> Result<(), String> {
+        let row_size = (self.width * 3).div_ceil(4) * 4;
+        let data_size = row_size * self.height;
+        let file_size = 54 + data_size;
+
+        let mut bytes = Vec::with_capacity(file_size);
+        bytes.extend_from_slice(b"BM");
+        bytes.extend_from_slice(&(file_size as u32).to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&54u32.to_le_bytes());
+        bytes.extend_from_slice(&40u32.to_le_bytes());
+        bytes.extend_from_slice(&(self.width as i32).to_le_bytes());
+        bytes.extend_from_slice(&(self.height as i32).to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&24u16.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&(data_size as u32).to_le_bytes());
+        bytes.extend_from_slice(&2835u32.to_le_bytes());
+        bytes.extend_from_slice(&2835u32.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+
+        // Las filas van de abajo hacia arriba, en orden BGR y alineadas a 4 bytes
+        for y in (0..self.height).rev() {
+            let row = &self.pixels[y * self.width..(y + 1) * self.width];
+            for color in row {
+                bytes.extend_from_slice(&[color.b, color.g, color.r]);
+            }
+            bytes.resize(bytes.len() + row_size - self.width * 3, 0);
+        }
+
+        fs::write(path, bytes).map_err(|e| format!("No se pudo escribir {path}: {e}"))
+    }
+
+    // Guarda PNG y BMP con marca de tiempo en `dir`; devuelve la ruta del PNG
+    pub fn save_screenshot(&self, dir: &str) -> Result<String, String> {
+        fs::create_dir_all(dir).map_err(|e| format!("No se pudo crear {dir}: {e}"))?;
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        let base = Path::new(dir).join(format!("captura_{timestamp}"));
+        let base = base.to_string_lossy();
+
+        let png = format!("{base}.png");
+        self.save_png(&png)?;
+        self.save_bmp(&format!("{base}.bmp"))?;
+        Ok(png)
+    }
+
+    // Sube los pixeles a la textura persistente (una sola copia por cuadro)
+    pub fn upload(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread) -> Result<(), String> {
+        let bytes = unsafe {
+            slice::from_raw_parts(self.pixels.as_ptr() as *const u8, self.pixels.len() * 4)
+        };
+
+        match &mut self.texture {
+            Some(texture) => texture.update_texture(bytes).map_err(|e| e.to_string()),
+            None => {
+                let texture = rl
+                    .load_texture_from_image(thread, &self.to_image())
+                    .map_err(|e| e.to_string())?;
+                self.texture = Some(texture);
+                Ok(())
             }
         }
     }
+
+    // Dibuja la última textura subida escalada al rectángulo de destino
+    pub fn draw(&self, d: &mut RaylibDrawHandle, dest_width: i32, dest_height: i32) {
+        if let Some(texture) = &self.texture {
+            d.draw_texture_pro(
+                texture,
+                Rectangle::new(0.0, 0.0, self.width as f32, self.height as f32),
+                Rectangle::new(0.0, 0.0, dest_width as f32, dest_height as f32),
+                Vector2::zero(),
+                0.0,
+                Color::WHITE,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Framebuffer {
+        let mut framebuffer = Framebuffer::new(5, 3, Color::DARKBLUE);
+        framebuffer.set(0, 0, Color::RED);
+        framebuffer.set(4, 2, Color::new(10, 20, 30, 128));
+        framebuffer.set(2, 1, Color::WHITE);
+        framebuffer
+    }
+
+    #[test]
+    fn image_round_trip_keeps_every_pixel() {
+        let framebuffer = sample();
+        let image = framebuffer.to_image();
+        assert_eq!((image.width, image.height), (5, 3));
+        let copy = Framebuffer::from_image(&image);
+        assert_eq!((copy.width(), copy.height()), (5, 3));
+        assert_eq!(copy.pixels, framebuffer.pixels);
+        assert_eq!(copy.diff_count(&framebuffer), Some(0));
+    }
+
+    #[test]
+    fn diff_count_needs_the_same_size() {
+        let mut changed = sample();
+        changed.set(1, 1, Color::GREEN);
+        changed.set(9, 9, Color::GREEN); // Fuera del cuadro: no se dibuja
+        assert_eq!(sample().diff_count(&changed), Some(1));
+        let small = Framebuffer::new(3, 5, Color::DARKBLUE);
+        assert_eq!(sample().diff_count(&small), None);
+    }
 }
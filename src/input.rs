@@ -0,0 +1,492 @@
+use raylib::prelude::*;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+
+// Lo que el jugador quiere hacer, sin importar con qué tecla o botón
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveForward,
+    MoveBackward,
+    StrafeLeft,
+    StrafeRight,
+    TurnLeft,
+    TurnRight,
+    Use,
+    Fire,
+    Pause,
+    Screenshot,
+    ShowPath,
+    QuickSave,
+    QuickLoad,
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    Confirm,
+}
+
+impl Action {
+    pub const ALL: [Action; 18] = [
+        Action::MoveForward,
+        Action::MoveBackward,
+        Action::StrafeLeft,
+        Action::StrafeRight,
+        Action::TurnLeft,
+        Action::TurnRight,
+        Action::Use,
+        Action::Fire,
+        Action::Pause,
+        Action::Screenshot,
+        Action::ShowPath,
+        Action::QuickSave,
+        Action::QuickLoad,
+        Action::MenuUp,
+        Action::MenuDown,
+        Action::MenuLeft,
+        Action::MenuRight,
+        Action::Confirm,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MoveForward => "move_forward",
+            Action::MoveBackward => "move_backward",
+            Action::StrafeLeft => "strafe_left",
+            Action::StrafeRight => "strafe_right",
+            Action::TurnLeft => "turn_left",
+            Action::TurnRight => "turn_right",
+            Action::Use => "use",
+            Action::Fire => "fire",
+            Action::Pause => "pause",
+            Action::Screenshot => "screenshot",
+            Action::ShowPath => "show_path",
+            Action::QuickSave => "quick_save",
+            Action::QuickLoad => "quick_load",
+            Action::MenuUp => "menu_up",
+            Action::MenuDown => "menu_down",
+            Action::MenuLeft => "menu_left",
+            Action::MenuRight => "menu_right",
+            Action::Confirm => "confirm",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.name() == name)
+    }
+}
+
+// Entrada física que dispara una acción
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    Key(KeyboardKey),
+    Mouse(MouseButton),
+    Pad(GamepadButton),
+}
+
+const KEY_NAMES: [(&str, KeyboardKey); 58] = [
+    ("A", KeyboardKey::KEY_A),
+    ("B", KeyboardKey::KEY_B),
+    ("C", KeyboardKey::KEY_C),
+    ("D", KeyboardKey::KEY_D),
+    ("E", KeyboardKey::KEY_E),
+    ("F", KeyboardKey::KEY_F),
+    ("G", KeyboardKey::KEY_G),
+    ("H", KeyboardKey::KEY_H),
+    ("I", KeyboardKey::KEY_I),
+    ("J", KeyboardKey::KEY_J),
+    ("K", KeyboardKey::KEY_K),
+    ("L", KeyboardKey::KEY_L),
+    ("M", KeyboardKey::KEY_M),
+    ("N", KeyboardKey::KEY_N),
+    ("O", KeyboardKey::KEY_O),
+    ("P", KeyboardKey::KEY_P),
+    ("Q", KeyboardKey::KEY_Q),
+    ("R", KeyboardKey::KEY_R),
+    ("S", KeyboardKey::KEY_S),
+    ("T", KeyboardKey::KEY_T),
+    ("U", KeyboardKey::KEY_U),
+    ("V", KeyboardKey::KEY_V),
+    ("W", KeyboardKey::KEY_W),
+    ("X", KeyboardKey::KEY_X),
+    ("Y", KeyboardKey::KEY_Y),
+    ("Z", KeyboardKey::KEY_Z),
+    ("0", KeyboardKey::KEY_ZERO),
+    ("1", KeyboardKey::KEY_ONE),
+    ("2", KeyboardKey::KEY_TWO),
+    ("3", KeyboardKey::KEY_THREE),
+    ("4", KeyboardKey::KEY_FOUR),
+    ("5", KeyboardKey::KEY_FIVE),
+    ("6", KeyboardKey::KEY_SIX),
+    ("7", KeyboardKey::KEY_SEVEN),
+    ("8", KeyboardKey::KEY_EIGHT),
+    ("9", KeyboardKey::KEY_NINE),
+    ("UP", KeyboardKey::KEY_UP),
+    ("DOWN", KeyboardKey::KEY_DOWN),
+    ("LEFT", KeyboardKey::KEY_LEFT),
+    ("RIGHT", KeyboardKey::KEY_RIGHT),
+    ("SPACE", KeyboardKey::KEY_SPACE),
+    ("ENTER", KeyboardKey::KEY_ENTER),
+    ("ESCAPE", KeyboardKey::KEY_ESCAPE),
+    ("TAB", KeyboardKey::KEY_TAB),
+    ("BACKSPACE", KeyboardKey::KEY_BACKSPACE),
+    ("SHIFT", KeyboardKey::KEY_LEFT_SHIFT),
+    ("CONTROL", KeyboardKey::KEY_LEFT_CONTROL),
+    ("ALT", KeyboardKey::KEY_LEFT_ALT),
+    ("F1", KeyboardKey::KEY_F1),
+    ("F2", KeyboardKey::KEY_F2),
+    ("F3", KeyboardKey::KEY_F3),
+    ("F4", KeyboardKey::KEY_F4),
+    ("F5", KeyboardKey::KEY_F5),
+    ("F6", KeyboardKey::KEY_F6),
+    ("F7", KeyboardKey::KEY_F7),
+    ("F8", KeyboardKey::KEY_F8),
+    ("F9", KeyboardKey::KEY_F9),
+    ("F12", KeyboardKey::KEY_F12),
+];
+
+const MOUSE_NAMES: [(&str, MouseButton); 3] = [
+    ("MOUSE_LEFT", MouseButton::MOUSE_BUTTON_LEFT),
+    ("MOUSE_RIGHT", MouseButton::MOUSE_BUTTON_RIGHT),
+    ("MOUSE_MIDDLE", MouseButton::MOUSE_BUTTON_MIDDLE),
+];
+
+// Nombres al estilo de un control de Xbox
+const PAD_NAMES: [(&str, GamepadButton); 12] = [
+    ("PAD_A", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN),
+    ("PAD_B", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT),
+    ("PAD_X", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT),
+    ("PAD_Y", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_UP),
+    ("PAD_UP", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP),
+    ("PAD_DOWN", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN),
+    ("PAD_LEFT", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT),
+    ("PAD_RIGHT", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT),
+    ("PAD_LB", GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_1),
+    ("PAD_RB", GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_1),
+    ("PAD_BACK", GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT),
+    ("PAD_START", GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT),
+];
+
+fn lookup<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    table
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|&(_, value)| value)
+}
+
+impl Binding {
+    pub fn from_name(name: &str) -> Option<Binding> {
+        let name = name.to_uppercase();
+        lookup(&KEY_NAMES, &name)
+            .map(Binding::Key)
+            .or_else(|| lookup(&MOUSE_NAMES, &name).map(Binding::Mouse))
+            .or_else(|| lookup(&PAD_NAMES, &name).map(Binding::Pad))
+    }
+
+    fn is_down(self, rl: &RaylibHandle, pad: Option<i32>) -> bool {
+        match self {
+            Binding::Key(key) => rl.is_key_down(key),
+            Binding::Mouse(button) => rl.is_mouse_button_down(button),
+            Binding::Pad(button) => pad.is_some_and(|pad| rl.is_gamepad_button_down(pad, button)),
+        }
+    }
+
+    fn is_pressed(self, rl: &RaylibHandle, pad: Option<i32>) -> bool {
+        match self {
+            Binding::Key(key) => rl.is_key_pressed(key),
+            Binding::Mouse(button) => rl.is_mouse_button_pressed(button),
+            Binding::Pad(button) => {
+                pad.is_some_and(|pad| rl.is_gamepad_button_pressed(pad, button))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ControlsError {
+    Io(io::Error),
+    UnknownAction { line: usize, action: String },
+    UnknownInput { line: usize, input: String },
+    InvalidValue { line: usize, value: String },
+}
+
+impl fmt::Display for ControlsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ControlsError::Io(e) => write!(f, "{e}"),
+            ControlsError::UnknownAction { line, action } => {
+                write!(f, "línea {line}: acción desconocida '{action}'")
+            }
+            ControlsError::UnknownInput { line, input } => {
+                write!(f, "línea {line}: tecla o botón desconocido '{input}'")
+            }
+            ControlsError::InvalidValue { line, value } => {
+                write!(f, "línea {line}: valor inválido '{value}'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ControlsError {}
+
+impl From<io::Error> for ControlsError {
+    fn from(e: io::Error) -> Self {
+        ControlsError::Io(e)
+    }
+}
+
+// Asignación de entradas a acciones, más los ejes del control
+pub struct Controls {
+    bindings: Vec<(Action, Binding)>,
+    pub deadzone: f32, // Movimiento mínimo de las palancas
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        let defaults = [
+            (Action::MoveForward, "W"),
+            (Action::MoveForward, "UP"),
+            (Action::MoveBackward, "S"),
+            (Action::MoveBackward, "DOWN"),
+            (Action::StrafeLeft, "Q"),
+            (Action::StrafeRight, "E"),
+            (Action::TurnLeft, "A"),
+            (Action::TurnLeft, "LEFT"),
+            (Action::TurnRight, "D"),
+            (Action::TurnRight, "RIGHT"),
+            (Action::Use, "SPACE"),
+            (Action::Use, "PAD_X"),
+            (Action::Fire, "CONTROL"),
+            (Action::Fire, "MOUSE_LEFT"),
+            (Action::Fire, "PAD_RB"),
+            (Action::Pause, "ESCAPE"),
+            (Action::Pause, "P"),
+            (Action::Pause, "PAD_START"),
+            (Action::Screenshot, "F12"),
+            (Action::ShowPath, "H"),
+            (Action::ShowPath, "PAD_Y"),
+            (Action::QuickSave, "F5"),
+            (Action::QuickLoad, "F9"),
+            (Action::MenuUp, "UP"),
+            (Action::MenuUp, "PAD_UP"),
+            (Action::MenuDown, "DOWN"),
+            (Action::MenuDown, "PAD_DOWN"),
+            (Action::MenuLeft, "LEFT"),
+            (Action::MenuLeft, "PAD_LEFT"),
+            (Action::MenuRight, "RIGHT"),
+            (Action::MenuRight, "PAD_RIGHT"),
+            (Action::Confirm, "ENTER"),
+            (Action::Confirm, "PAD_A"),
+        ];
+        Controls {
+            bindings: defaults
+                .into_iter()
+                .filter_map(|(action, name)| Binding::from_name(name).map(|b| (action, b)))
+                .collect(),
+            deadzone: 0.2,
+        }
+    }
+}
+
+impl Controls {
+    // Sin archivo se usan los controles por defecto
+    pub fn load(path: &str) -> Result<Controls, ControlsError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Controls::parse(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Controls::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // Formato: una acción por línea seguida de sus entradas; '#' empieza un comentario.
+    //   move_forward W UP PAD_UP
+    //   deadzone 0.25
+    // Las acciones que no aparecen conservan sus entradas por defecto.
+    pub fn parse(text: &str) -> Result<Controls, ControlsError> {
+        let mut controls = Controls::default();
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let line = line.split('#').next().unwrap_or("");
+            let mut words = line.split_whitespace();
+            let Some(key) = words.next() else { continue };
+
+            if key == "deadzone" {
+                let value = words.next().unwrap_or("");
+                controls.deadzone = value.parse().map_err(|_| ControlsError::InvalidValue {
+                    line: number,
+                    value: value.to_string(),
+                })?;
+                continue;
+            }
+
+            let action = Action::from_name(key).ok_or_else(|| ControlsError::UnknownAction {
+                line: number,
+                action: key.to_string(),
+            })?;
+            controls.bindings.retain(|&(a, _)| a != action);
+            for name in words {
+                let binding =
+                    Binding::from_name(name).ok_or_else(|| ControlsError::UnknownInput {
+                        line: number,
+                        input: name.to_string(),
+                    })?;
+                controls.bindings.push((action, binding));
+            }
+        }
+        Ok(controls)
+    }
+
+    // Lee el estado de todas las entradas en este cuadro
+    pub fn read(&self, rl: &RaylibHandle) -> InputState {
+        let pad = (0..4).find(|&pad| rl.is_gamepad_available(pad));
+        let mut state = InputState::default();
+        for &(action, binding) in &self.bindings {
+            if binding.is_down(rl, pad) {
+                state.down.insert(action);
+            }
+            if binding.is_pressed(rl, pad) {
+                state.pressed.insert(action);
+            }
+        }
+
+        // Las palancas suman a las teclas; bajo la zona muerta se ignoran
+        let stick = |axis: GamepadAxis| {
+            let value = pad.map_or(0.0, |pad| rl.get_gamepad_axis_movement(pad, axis));
+            if value.abs() < self.deadzone {
+                0.0
+            } else {
+                value
+            }
+        };
+
+        state.forward = (state.axis(Action::MoveForward, Action::MoveBackward)
+            - stick(GamepadAxis::GAMEPAD_AXIS_LEFT_Y))
+        .clamp(-1.0, 1.0);
+        state.strafe = (state.axis(Action::StrafeRight, Action::StrafeLeft)
+            + stick(GamepadAxis::GAMEPAD_AXIS_LEFT_X))
+        .clamp(-1.0, 1.0);
+        state.turn = (state.axis(Action::TurnRight, Action::TurnLeft)
+            + stick(GamepadAxis::GAMEPAD_AXIS_RIGHT_X))
+        .clamp(-1.0, 1.0);
+        state.look = rl.get_mouse_delta().x;
+        state
+    }
+}
+
+// Entradas de un cuadro ya traducidas a acciones; se puede armar a mano sin ventana
+#[derive(Debug, Clone, Default)]
+pub struct InputState {
+    pub forward: f32, // -1..1, positivo hacia adelante
+    pub strafe: f32,  // -1..1, positivo hacia la derecha
+    pub turn: f32,    // -1..1, positivo en sentido horario
+    pub look: f32,    // Desplazamiento horizontal del mouse en pixeles
+    pub down: HashSet<Action>,
+    pub pressed: HashSet<Action>, // Solo en el cuadro en que se presionó
+}
+
+impl InputState {
+    pub fn is_down(&self, action: Action) -> bool {
+        self.down.contains(&action)
+    }
+
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.forward != 0.0 || self.strafe != 0.0 || self.turn != 0.0
+    }
+
+    // +1, -1 o 0 según cuál de las dos acciones está presionada
+    fn axis(&self, positive: Action, negative: Action) -> f32 {
+        self.is_down(positive) as i32 as f32 - self.is_down(negative) as i32 as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings_of(controls: &Controls, action: Action) -> Vec<Binding> {
+        controls
+            .bindings
+            .iter()
+            .filter(|&&(a, _)| a == action)
+            .map(|&(_, binding)| binding)
+            .collect()
+    }
+
+    #[test]
+    fn shipped_file_matches_the_defaults() {
+        let shipped = Controls::parse(include_str!("../controls.cfg")).unwrap();
+        let defaults = Controls::default();
+        assert_eq!(shipped.bindings, defaults.bindings);
+        assert_eq!(shipped.deadzone, defaults.deadzone);
+        // Todos los nombres de los valores por defecto existen
+        assert_eq!(defaults.bindings.len(), 33);
+    }
+
+    #[test]
+    fn lines_replace_the_default_inputs() {
+        let text = "\
+# Solo flechas para caminar
+move_forward up   # sin W
+fire mouse_right Pad_LB
+deadzone 0.35
+";
+        let controls = Controls::parse(text).unwrap();
+        assert_eq!(
+            bindings_of(&controls, Action::MoveForward),
+            vec![Binding::Key(KeyboardKey::KEY_UP)]
+        );
+        assert_eq!(
+            bindings_of(&controls, Action::Fire),
+            vec![
+                Binding::Mouse(MouseButton::MOUSE_BUTTON_RIGHT),
+                Binding::Pad(GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_1),
+            ]
+        );
+        assert_eq!(controls.deadzone, 0.35);
+        // Lo que no aparece queda como estaba
+        assert_eq!(
+            bindings_of(&controls, Action::Use),
+            bindings_of(&Controls::default(), Action::Use)
+        );
+    }
+
+    #[test]
+    fn a_repeated_action_keeps_the_last_line() {
+        let controls = Controls::parse("use E\nuse F PAD_B\n").unwrap();
+        assert_eq!(
+            bindings_of(&controls, Action::Use),
+            vec![
+                Binding::Key(KeyboardKey::KEY_F),
+                Binding::Pad(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT),
+            ]
+        );
+        // Una acción sin entradas queda desactivada
+        let controls = Controls::parse("screenshot\n").unwrap();
+        assert!(bindings_of(&controls, Action::Screenshot).is_empty());
+    }
+
+    #[test]
+    fn reports_the_line_of_bad_entries() {
+        match Controls::parse("use SPACE\n\njump SPACE\n") {
+            Err(ControlsError::UnknownAction { line: 3, action }) => assert_eq!(action, "jump"),
+            other => panic!("se esperaba una acción desconocida: {:?}", other.err()),
+        }
+        match Controls::parse("fire MOUSE_LEFT PAD_Z\n") {
+            Err(ControlsError::UnknownInput { line: 1, input }) => assert_eq!(input, "PAD_Z"),
+            other => panic!("se esperaba una entrada desconocida: {:?}", other.err()),
+        }
+        assert!(matches!(
+            Controls::parse("# comentario\ndeadzone mucho\n"),
+            Err(ControlsError::InvalidValue { line: 2, .. })
+        ));
+        assert!(matches!(
+            Controls::parse("deadzone\n"),
+            Err(ControlsError::InvalidValue { line: 1, .. })
+        ));
+    }
+}
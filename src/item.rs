@@ -0,0 +1,264 @@
+use raylib::prelude::*;
+use crate::animation::Animation;
+use crate::sprite::Sprite;
+use crate::textures::{TextureImage, TextureSet};
+
+pub const PICKUP_RADIUS: f32 = 0.4; // Distancia para recoger un objeto, en celdas
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyColor {
+    Red,
+    Blue,
+    Yellow,
+    Green,
+}
+
+impl KeyColor {
+    pub const ALL: [KeyColor; 4] = [
+        KeyColor::Red,
+        KeyColor::Blue,
+        KeyColor::Yellow,
+        KeyColor::Green,
+    ];
+
+    // Nombre en los archivos de nivel
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyColor::Red => "red",
+            KeyColor::Blue => "blue",
+            KeyColor::Yellow => "yellow",
+            KeyColor::Green => "green",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<KeyColor> {
+        KeyColor::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    // Nombre en pantalla
+    pub fn label(self) -> &'static str {
+        match self {
+            KeyColor::Red => "roja",
+            KeyColor::Blue => "azul",
+            KeyColor::Yellow => "amarilla",
+            KeyColor::Green => "verde",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            KeyColor::Red => Color::RED,
+            KeyColor::Blue => Color::BLUE,
+            KeyColor::Yellow => Color::YELLOW,
+            KeyColor::Green => Color::GREEN,
+        }
+    }
+
+    pub fn sprite_name(self) -> &'static str {
+        match self {
+            KeyColor::Red => "key_red",
+            KeyColor::Blue => "key_blue",
+            KeyColor::Yellow => "key_yellow",
+            KeyColor::Green => "key_green",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Item {
+    Key(KeyColor),
+    Coin,
+    Star,
+    Health, // Botiquín: devuelve salud
+    Armor,
+    Ammo, // Balas para el arma
+}
+
+impl Item {
+    // Nombre en las partidas guardadas
+    pub fn name(self) -> String {
+        match self {
+            Item::Key(color) => format!("key_{}", color.name()),
+            Item::Coin => "coin".to_string(),
+            Item::Star => "star".to_string(),
+            Item::Health => "health".to_string(),
+            Item::Armor => "armor".to_string(),
+            Item::Ammo => "ammo".to_string(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Item> {
+        match name {
+            "coin" => Some(Item::Coin),
+            "star" => Some(Item::Star),
+            "health" => Some(Item::Health),
+            "armor" => Some(Item::Armor),
+            "ammo" => Some(Item::Ammo),
+            _ => KeyColor::from_name(name.strip_prefix("key_")?).map(Item::Key),
+        }
+    }
+
+    // Con artículo, para los mensajes
+    pub fn label(self) -> String {
+        match self {
+            Item::Key(color) => format!("la llave {}", color.label()),
+            Item::Coin => "la moneda".to_string(),
+            Item::Star => "la estrella".to_string(),
+            Item::Health => "el botiquín".to_string(),
+            Item::Armor => "la armadura".to_string(),
+            Item::Ammo => "la munición".to_string(),
+        }
+    }
+
+    pub fn score(self) -> u32 {
+        match self {
+            Item::Key(_) | Item::Health | Item::Armor | Item::Ammo => 0,
+            Item::Coin => 10,
+            Item::Star => 50,
+        }
+    }
+
+    // Solo las llaves se guardan; lo demás se usa al recogerlo
+    pub fn is_kept(self) -> bool {
+        matches!(self, Item::Key(_))
+    }
+
+    pub fn sprite(self) -> Sprite {
+        let animation = match self {
+            Item::Key(color) => Animation::new(vec![color.sprite_name()], 1.0),
+            Item::Coin => Animation::new(vec!["coin1", "coin2", "coin3", "coin2"], 0.12),
+            Item::Star => Animation::new(vec!["star1", "star2", "star3"], 0.2),
+            Item::Health => Animation::new(vec!["health"], 1.0),
+            Item::Armor => Animation::new(vec!["armor"], 1.0),
+            Item::Ammo => Animation::new(vec!["ammo"], 1.0),
+        };
+        Sprite::new(0.3, animation)
+    }
+}
+
+// Objetos que lleva el jugador
+#[derive(Default)]
+pub struct Inventory {
+    items: Vec<Item>,
+}
+
+impl Inventory {
+    pub fn add(&mut self, item: Item) {
+        self.items.push(item);
+    }
+
+    pub fn has_key(&self, color: KeyColor) -> bool {
+        self.items.contains(&Item::Key(color))
+    }
+
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+}
+
+// Imágenes de los objetos: las estrellas vienen de archivo y lo demás se genera
+pub fn load_textures(set: &mut TextureSet) -> Result<(), String> {
+    for (name, path) in [
+        ("star1", "assets/star1.png"),
+        ("star2", "assets/star2.png"),
+        ("star3", "assets/star3.png"),
+    ] {
+        let mut image = TextureImage::load(path)?;
+        image.clear_border_background(40);
+        set.insert_sprite(name, image);
+    }
+    for (name, squash) in [("coin1", 1.0), ("coin2", 0.6), ("coin3", 0.2)] {
+        set.insert_sprite(name, coin_image(squash));
+    }
+    for color in KeyColor::ALL {
+        set.insert_sprite(color.sprite_name(), key_image(color.color()));
+    }
+    set.insert_sprite("health", medkit_image());
+    set.insert_sprite("armor", armor_image());
+    set.insert_sprite("ammo", ammo_image());
+    Ok(())
+}
+
+// Llave de perfil: argolla a la izquierda, vástago y dientes; fondo transparente
+fn key_image(color: Color) -> TextureImage {
+    TextureImage::generate(32, |x, y| {
+        let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+        let ring = ((x - 9.0).powi(2) + (y - 16.0).powi(2)).sqrt();
+        let shaft = (14.0..30.0).contains(&x) && (14.0..18.0).contains(&y);
+        let teeth =
+            (18.0..23.0).contains(&y) && ((20.0..23.0).contains(&x) || (26.0..30.0).contains(&x));
+        if (3.5..7.0).contains(&ring) || shaft || teeth {
+            color
+        } else {
+            Color::new(0, 0, 0, 0)
+        }
+    })
+}
+
+// Moneda vista de frente con `squash` = 1.0, de canto cuando tiende a 0
+fn coin_image(squash: f32) -> TextureImage {
+    TextureImage::generate(32, |x, y| {
+        let x = (x as f32 + 0.5 - 16.0) / squash;
+        let y = y as f32 + 0.5 - 16.0;
+        let r = (x * x + y * y).sqrt();
+        if r < 9.0 {
+            Color::GOLD
+        } else if r < 12.0 {
+            Color::ORANGE
+        } else {
+            Color::new(0, 0, 0, 0)
+        }
+    })
+}
+
+// Botiquín blanco con cruz roja; fondo transparente
+fn medkit_image() -> TextureImage {
+    TextureImage::generate(32, |x, y| {
+        let cross = ((13..19).contains(&x) && (10..26).contains(&y))
+            || ((8..24).contains(&x) && (15..21).contains(&y));
+        if cross {
+            Color::RED
+        } else if (4..28).contains(&x) && (6..30).contains(&y) {
+            Color::WHITE
+        } else {
+            Color::new(0, 0, 0, 0)
+        }
+    })
+}
+
+// Escudo azul con borde claro; fondo transparente
+fn armor_image() -> TextureImage {
+    TextureImage::generate(32, |x, y| {
+        let (x, y) = (x as f32 + 0.5 - 16.0, y as f32 + 0.5);
+        // Lados rectos arriba que se cierran en punta hacia abajo
+        let half_width = if y < 16.0 {
+            12.0
+        } else {
+            12.0 * (30.0 - y) / 14.0
+        };
+        let inside = (2.0..30.0).contains(&y) && x.abs() < half_width;
+        if inside && (x.abs() > half_width - 2.5 || y < 4.5) {
+            Color::SKYBLUE
+        } else if inside {
+            Color::DARKBLUE
+        } else {
+            Color::new(0, 0, 0, 0)
+        }
+    })
+}
+
+// Caja de balas con tres cartuchos asomando; fondo transparente
+fn ammo_image() -> TextureImage {
+    TextureImage::generate(32, |x, y| {
+        let bullet = (8..24).contains(&x) && x % 6 != 1 && (8..18).contains(&y);
+        if (6..26).contains(&x) && (16..30).contains(&y) {
+            Color::new(90, 110, 50, 255)
+        } else if bullet && y < 11 {
+            Color::new(180, 100, 40, 255)
+        } else if bullet {
+            Color::GOLD
+        } else {
+            Color::new(0, 0, 0, 0)
+        }
+    })
+}
@@ -0,0 +1,685 @@
+use raylib::prelude::*;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::enemy::EnemyKind;
+use crate::item::{Item, KeyColor};
+use crate::maze::generate::{self, Algorithm};
+use crate::maze::{Layer, Maze, MazeError, DOOR_TILE};
+use crate::pathfinding::{self, Cell};
+use crate::textures::DEFAULT_WALLS;
+use crate::trigger::{Trigger, TriggerAction, TriggerEvent};
+
+// Nivel completo: la cuadrícula más los metadatos del archivo
+pub struct Level {
+    pub name: String,
+    pub maze: Maze,
+    pub spawn: Vector2,
+    pub spawn_angle: f32,              // Radianes
+    pub textures: Vec<(char, String)>, // Texturas propias del nivel (letra, archivo)
+    pub music: Option<String>,
+    pub par_time: Option<f32>,        // Segundos
+    pub locks: Vec<(Cell, KeyColor)>, // Puertas que piden llave
+    pub items: Vec<(Vector2, Item)>,  // Objetos para recoger
+    // Enemigos: tipo y ruta de patrulla; la primera celda es donde aparece
+    pub enemies: Vec<(EnemyKind, Vec<Cell>)>,
+    pub triggers: Vec<Trigger>, // Zonas con eventos del nivel
+}
+
+// Celdas lógicas por lado del nivel aleatorio del menú; también es el tope que se
+// acepta al cargar una partida guardada
+pub const RANDOM_LEVEL_SIZE: usize = 8;
+
+// De dónde sale un nivel, para poder volver a armarlo igual (partidas guardadas)
+#[derive(Debug, Clone, PartialEq)]
+pub enum LevelSource {
+    File(String),
+    Random {
+        algorithm: Algorithm,
+        seed: u64,
+        size: usize, // Celdas lógicas por lado
+    },
+}
+
+impl LevelSource {
+    pub fn load(&self) -> Result<Level, String> {
+        match self {
+            LevelSource::File(path) => Level::load(path).map_err(|e| format!("{path}: {e}")),
+            LevelSource::Random {
+                algorithm,
+                seed,
+                size,
+            } => {
+                let options = generate::Options {
+                    width: *size,
+                    height: *size,
+                    seed: *seed,
+                    ..Default::default()
+                };
+                let name = format!("Aleatorio ({})", algorithm.name());
+                Level::from_maze(&name, generate::generate(*algorithm, &options))
+                    .map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    // Archivo del nivel; los aleatorios no tienen
+    pub fn file(&self) -> Option<&str> {
+        match self {
+            LevelSource::File(path) => Some(path),
+            LevelSource::Random { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(io::Error),
+    UnknownDirective { line: usize, directive: String },
+    MissingValue { line: usize, directive: String },
+    InvalidValue { line: usize, value: String },
+    NotADoor { x: usize, y: usize },
+    NotAWall { x: usize, y: usize },
+    InsideWall { x: usize, y: usize },
+    OnSpawn { x: usize, y: usize },
+    OutsideMap { x: usize, y: usize },
+    Maze(MazeError),
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelError::Io(e) => write!(f, "{e}"),
+            LevelError::UnknownDirective { line, directive } => {
+                write!(f, "línea {line}: directiva desconocida @{directive}")
+            }
+            LevelError::MissingValue { line, directive } => {
+                write!(f, "línea {line}: falta un valor para @{directive}")
+            }
+            LevelError::InvalidValue { line, value } => {
+                write!(f, "línea {line}: valor inválido '{value}'")
+            }
+            LevelError::NotADoor { x, y } => write!(f, "celda {x} {y}: ahí no hay una puerta"),
+            LevelError::NotAWall { x, y } => write!(f, "celda {x} {y}: ahí no hay una pared"),
+            LevelError::InsideWall { x, y } => {
+                write!(f, "celda {x} {y}: está dentro de una pared")
+            }
+            LevelError::OnSpawn { x, y } => {
+                write!(f, "celda {x} {y}: el objeto está donde empieza el jugador")
+            }
+            LevelError::OutsideMap { x, y } => write!(f, "celda {x} {y}: está fuera del mapa"),
+            LevelError::Maze(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl From<io::Error> for LevelError {
+    fn from(e: io::Error) -> Self {
+        LevelError::Io(e)
+    }
+}
+
+impl From<MazeError> for LevelError {
+    fn from(e: MazeError) -> Self {
+        LevelError::Maze(e)
+    }
+}
+
+impl Level {
+    // Sin @name, el nivel se llama como el archivo
+    pub fn load(path: &str) -> Result<Level, LevelError> {
+        let text = fs::read_to_string(path)?;
+        let mut level = Level::parse(&text)?;
+        if level.name.is_empty() {
+            level.name = Path::new(path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.to_string());
+        }
+        Ok(level)
+    }
+
+    // Formato: la cuadrícula de paredes (P = inicio, G = meta, ~ = lava, ^ = pinchos),
+    // más directivas con '@'.
+    //   @name texto          nombre que se muestra en el menú
+    //   @spawn x y [grados]  posición y ángulo de inicio (reemplaza la marca P)
+    //   @goal x y            celda de meta adicional; puede repetirse
+    //   @texture X archivo   textura PNG para la letra X
+    //   @music archivo       música del nivel
+    //   @par segundos        tiempo de referencia
+    //   @key x y color       llave (red, blue, yellow, green) en el centro de la celda
+    //   @coin x y / @star x y  moneda (10 puntos) o estrella (50 puntos)
+    //   @health x y / @armor x y  botiquín o armadura
+    //   @ammo x y            balas para el arma
+    //   @lock x y color      la puerta (D) en x, y solo abre con la llave de ese color
+    //   @enemy x y tipo [x y ...]  enemigo (guard, hound) que patrulla por esas celdas
+    //   @trigger x y [x2 y2] evento acción  zona de una celda o un rectángulo que
+    //       reacciona al entrar, salir o usar (enter, exit, use). Acciones:
+    //       message texto | door x y | sound archivo | teleport x y [grados]
+    //       | wall x y X (cambia la textura de esa pared) | end (termina el nivel)
+    //   @floor X / @ceiling X  textura de suelo o techo por defecto
+    //   @floor / @ceiling solos: las líneas siguientes eligen la textura de cada celda
+    pub fn parse(text: &str) -> Result<Level, LevelError> {
+        let mut grid = Vec::new();
+        let mut grid_lines = Vec::new();
+        let mut floor = Layer::default();
+        let mut ceiling = Layer::default();
+
+        let mut name = String::new();
+        let mut spawn = None;
+        let mut spawn_angle = None;
+        let mut goals = Vec::new();
+        let mut textures = Vec::new();
+        let mut music = None;
+        let mut par_time = None;
+        let mut locks = Vec::new();
+        let mut items = Vec::new();
+        let mut enemies = Vec::new();
+        let mut triggers = Vec::new();
+
+        let mut section: Option<&str> = None;
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let Some(directive) = line.strip_prefix('@') else {
+                let row = line.chars().collect();
+                match section {
+                    Some("floor") => floor.cells.push(row),
+                    Some("ceiling") => ceiling.cells.push(row),
+                    _ => {
+                        grid.push(row);
+                        grid_lines.push(number);
+                    }
+                }
+                continue;
+            };
+
+            let (key, rest) = directive
+                .split_once(char::is_whitespace)
+                .unwrap_or((directive, ""));
+            let rest = rest.trim();
+            let values: Vec<&str> = rest.split_whitespace().collect();
+            let missing = || LevelError::MissingValue {
+                line: number,
+                directive: key.to_string(),
+            };
+            let number_at = |i: usize| -> Result<f32, LevelError> {
+                let value = values.get(i).ok_or_else(missing)?;
+                value.parse().map_err(|_| LevelError::InvalidValue {
+                    line: number,
+                    value: value.to_string(),
+                })
+            };
+            let cell_at = |i: usize| -> Result<Cell, LevelError> {
+                let (x, y) = (number_at(i)?, number_at(i + 1)?);
+                if x < 0.0 || y < 0.0 {
+                    return Err(LevelError::InvalidValue {
+                        line: number,
+                        value: rest.to_string(),
+                    });
+                }
+                Ok((x as usize, y as usize))
+            };
+            let key_at = |i: usize| -> Result<KeyColor, LevelError> {
+                let value = values.get(i).ok_or_else(missing)?;
+                KeyColor::from_name(value).ok_or_else(|| LevelError::InvalidValue {
+                    line: number,
+                    value: value.to_string(),
+                })
+            };
+
+            match key {
+                "floor" | "ceiling" => {
+                    let layer = if key == "floor" {
+                        &mut floor
+                    } else {
+                        &mut ceiling
+                    };
+                    match rest.chars().next() {
+                        Some(ch) => {
+                            layer.default = Some(ch);
+                            section = None;
+                        }
+                        None => section = Some(key),
+                    }
+                    continue;
+                }
+                "name" if rest.is_empty() => return Err(missing()),
+                "name" => name = rest.to_string(),
+                "spawn" => {
+                    let pos = Vector2::new(number_at(0)?, number_at(1)?);
+                    if !(pos.x.is_finite() && pos.y.is_finite()) || pos.x < 0.0 || pos.y < 0.0 {
+                        return Err(LevelError::InvalidValue {
+                            line: number,
+                            value: rest.to_string(),
+                        });
+                    }
+                    spawn = Some(pos);
+                    if values.len() > 2 {
+                        spawn_angle = Some(number_at(2)?.to_radians());
+                    }
+                }
+                "goal" => goals.push(cell_at(0)?),
+                "texture" => match (values.first(), values.get(1)) {
+                    (Some(ch), Some(path)) if ch.chars().count() == 1 => {
+                        textures.push((ch.chars().next().unwrap(), path.to_string()))
+                    }
+                    (Some(ch), Some(_)) => {
+                        return Err(LevelError::InvalidValue {
+                            line: number,
+                            value: ch.to_string(),
+                        })
+                    }
+                    _ => return Err(missing()),
+                },
+                "music" if rest.is_empty() => return Err(missing()),
+                "music" => music = Some(rest.to_string()),
+                "par" => par_time = Some(number_at(0)?),
+                "lock" => locks.push((cell_at(0)?, key_at(2)?)),
+                "key" => {
+                    let (x, y) = cell_at(0)?;
+                    let pos = Vector2::new(x as f32 + 0.5, y as f32 + 0.5);
+                    items.push((pos, Item::Key(key_at(2)?)));
+                }
+                "coin" | "star" | "health" | "armor" | "ammo" => {
+                    let item = match key {
+                        "coin" => Item::Coin,
+                        "star" => Item::Star,
+                        "health" => Item::Health,
+                        "armor" => Item::Armor,
+                        _ => Item::Ammo,
+                    };
+                    let (x, y) = cell_at(0)?;
+                    items.push((Vector2::new(x as f32 + 0.5, y as f32 + 0.5), item));
+                }
+                "enemy" => {
+                    let value = values.get(2).ok_or_else(missing)?;
+                    let kind =
+                        EnemyKind::from_name(value).ok_or_else(|| LevelError::InvalidValue {
+                            line: number,
+                            value: value.to_string(),
+                        })?;
+                    // La celda de inicio y después los puntos de patrulla, de a pares
+                    let mut route = vec![cell_at(0)?];
+                    for i in (3..values.len()).step_by(2) {
+                        route.push(cell_at(i)?);
+                    }
+                    enemies.push((kind, route));
+                }
+                "trigger" => {
+                    // La segunda esquina es opcional: si después de x y viene un
+                    // número, la zona es un rectángulo
+                    let first = cell_at(0)?;
+                    let (area, next) = match values.get(2).map(|v| v.parse::<f32>()) {
+                        Some(Ok(_)) => ((first, cell_at(2)?), 4),
+                        _ => ((first, first), 2),
+                    };
+                    let invalid = |i: usize| LevelError::InvalidValue {
+                        line: number,
+                        value: values.get(i).unwrap_or(&"").to_string(),
+                    };
+                    let event = values
+                        .get(next)
+                        .ok_or_else(missing)
+                        .and_then(|v| TriggerEvent::from_name(v).ok_or_else(|| invalid(next)))?;
+                    let at = next + 2; // Primer valor de la acción
+                    let text = values.get(at..).unwrap_or_default().join(" ");
+                    let action = match values.get(next + 1).copied().ok_or_else(missing)? {
+                        "message" | "sound" if text.is_empty() => return Err(missing()),
+                        "message" => TriggerAction::Message(text),
+                        "sound" => TriggerAction::Sound(text),
+                        "door" => TriggerAction::OpenDoor(cell_at(at)?),
+                        "teleport" => {
+                            let (x, y) = cell_at(at)?;
+                            TriggerAction::Teleport {
+                                pos: Vector2::new(x as f32 + 0.5, y as f32 + 0.5),
+                                angle: if values.len() > at + 2 {
+                                    Some(number_at(at + 2)?.to_radians())
+                                } else {
+                                    None
+                                },
+                            }
+                        }
+                        "wall" => match values.get(at + 2).map(|v| v.chars().collect::<Vec<_>>()) {
+                            Some(chars) if chars.len() == 1 => {
+                                TriggerAction::SetWall(cell_at(at)?, chars[0])
+                            }
+                            Some(_) => return Err(invalid(at + 2)),
+                            None => return Err(missing()),
+                        },
+                        "end" => TriggerAction::EndLevel,
+                        _ => return Err(invalid(next + 1)),
+                    };
+                    triggers.push((number, Trigger::new(area, event, action)));
+                }
+                _ => {
+                    return Err(LevelError::UnknownDirective {
+                        line: number,
+                        directive: key.to_string(),
+                    })
+                }
+            }
+            section = None;
+        }
+
+        // Las líneas vacías al final del archivo no son filas
+        while grid.last().is_some_and(|row: &Vec<char>| row.is_empty()) {
+            grid.pop();
+            grid_lines.pop();
+        }
+
+        let mut maze = Maze::new(grid, floor, ceiling);
+        maze.set_source_lines(grid_lines);
+        for cell in goals {
+            maze.add_goal(cell);
+        }
+
+        // La marca P cuenta solo si no hay @spawn, que tiene que caer en una celda libre
+        if let Some(pos) = spawn {
+            let (x, y) = (pos.x as usize, pos.y as usize);
+            if x >= maze.width() || y >= maze.height() {
+                return Err(LevelError::OutsideMap { x, y });
+            }
+            if maze.get_wall(pos.x, pos.y).is_some() {
+                return Err(LevelError::InsideWall { x, y });
+            }
+            maze.set_spawn((x, y));
+        }
+        let spawn = maze
+            .spawn()
+            .map(|(x, y)| spawn.unwrap_or(Vector2::new(x as f32 + 0.5, y as f32 + 0.5)))
+            .ok_or(MazeError::MissingSpawn)?;
+
+        let tiles: Vec<char> = DEFAULT_WALLS
+            .iter()
+            .map(|&(ch, _)| ch)
+            .chain(textures.iter().map(|&(ch, _)| ch))
+            .chain([DOOR_TILE])
+            .collect();
+        maze.validate(&tiles)?;
+        if let Some(((x, y), _)) = locks
+            .iter()
+            .find(|((x, y), _)| !maze.is_door(*x as i32, *y as i32))
+        {
+            return Err(LevelError::NotADoor { x: *x, y: *y });
+        }
+        // Los objetos van en celdas libres dentro del mapa, no en puertas
+        if let Some((pos, _)) = items.iter().find(|(pos, _)| {
+            let cell = (pos.x as usize, pos.y as usize);
+            !pathfinding::is_walkable(&maze, cell) || maze.is_door(pos.x as i32, pos.y as i32)
+        }) {
+            return Err(LevelError::InsideWall {
+                x: pos.x as usize,
+                y: pos.y as usize,
+            });
+        }
+        // Si no, se recogería en el primer cuadro sin que el jugador se mueva
+        let start = (spawn.x as usize, spawn.y as usize);
+        if let Some((pos, _)) = items
+            .iter()
+            .find(|(pos, _)| (pos.x as usize, pos.y as usize) == start)
+        {
+            return Err(LevelError::OnSpawn {
+                x: pos.x as usize,
+                y: pos.y as usize,
+            });
+        }
+        if let Some(&(x, y)) = enemies
+            .iter()
+            .flat_map(|(_, route)| route)
+            .find(|&&cell| !pathfinding::is_walkable(&maze, cell))
+        {
+            return Err(LevelError::InsideWall { x, y });
+        }
+        for (line, trigger) in &triggers {
+            check_trigger(&maze, &tiles, *line, trigger)?;
+        }
+        let triggers = triggers.into_iter().map(|(_, trigger)| trigger).collect();
+
+        Ok(Level {
+            name,
+            spawn_angle: spawn_angle.unwrap_or_else(|| open_direction(&maze, spawn)),
+            maze,
+            spawn,
+            textures,
+            music,
+            par_time,
+            locks,
+            items,
+            enemies,
+            triggers,
+        })
+    }
+
+    // Nivel sin archivo (por ejemplo, generado)
+    pub fn from_maze(name: &str, maze: Maze) -> Result<Level, LevelError> {
+        let (x, y) = maze.spawn().ok_or(MazeError::MissingSpawn)?;
+        let spawn = Vector2::new(x as f32 + 0.5, y as f32 + 0.5);
+        Ok(Level {
+            name: name.to_string(),
+            spawn_angle: open_direction(&maze, spawn),
+            maze,
+            spawn,
+            textures: Vec::new(),
+            music: None,
+            par_time: None,
+            locks: Vec::new(),
+            items: Vec::new(),
+            enemies: Vec::new(),
+            triggers: Vec::new(),
+        })
+    }
+
+    pub fn spawn_cell(&self) -> Cell {
+        (self.spawn.x as usize, self.spawn.y as usize)
+    }
+
+    // Largo del camino a la meta más cercana más un extra por bifurcación
+    pub fn difficulty(&self) -> Option<u32> {
+        pathfinding::difficulty(&self.maze, self.spawn_cell(), self.maze.goals())
+    }
+}
+
+// La zona tiene que estar dentro del mapa, y lo que apunta su acción tiene que
+// existir: la puerta, la pared y su nueva textura, o una celda libre adonde
+// llevar al jugador
+fn check_trigger(
+    maze: &Maze,
+    tiles: &[char],
+    line: usize,
+    trigger: &Trigger,
+) -> Result<(), LevelError> {
+    let inside_map = |(x, y): Cell| x < maze.width() && y < maze.height();
+    let (_, far_corner) = trigger.area;
+    if !inside_map(far_corner) {
+        let (x, y) = far_corner;
+        return Err(LevelError::OutsideMap { x, y });
+    }
+    match trigger.action {
+        TriggerAction::OpenDoor((x, y)) if !maze.is_door(x as i32, y as i32) => {
+            Err(LevelError::NotADoor { x, y })
+        }
+        TriggerAction::SetWall((x, y), ch) => {
+            let wall = maze.get_cell(x as i32, y as i32);
+            if wall.is_none() || maze.is_door(x as i32, y as i32) {
+                Err(LevelError::NotAWall { x, y })
+            } else if !tiles.contains(&ch) || ch == DOOR_TILE {
+                Err(LevelError::InvalidValue {
+                    line,
+                    value: ch.to_string(),
+                })
+            } else {
+                Ok(())
+            }
+        }
+        TriggerAction::Teleport { pos, .. } => {
+            let (x, y) = (pos.x as usize, pos.y as usize);
+            if !inside_map((x, y)) {
+                Err(LevelError::OutsideMap { x, y })
+            } else if !pathfinding::is_walkable(maze, (x, y)) || maze.is_door(x as i32, y as i32) {
+                Err(LevelError::InsideWall { x, y })
+            } else {
+                Ok(())
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+// Sin ángulo explícito, el jugador empieza mirando hacia el primer pasillo libre
+fn open_direction(maze: &Maze, pos: Vector2) -> f32 {
+    [0.0_f32, 90.0, 180.0, 270.0]
+        .into_iter()
+        .map(f32::to_radians)
+        .find(|a| maze.get_wall(pos.x + a.cos(), pos.y + a.sin()).is_none())
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL: &str = "\
+@name Pasillo
+@par 12.5
+#####
+#P G#
+#####
+";
+
+    #[test]
+    fn parses_metadata_and_grid() {
+        let level = Level::parse(SMALL).unwrap();
+        assert_eq!(level.name, "Pasillo");
+        assert_eq!(level.par_time, Some(12.5));
+        assert_eq!(level.spawn, Vector2::new(1.5, 1.5));
+        assert_eq!(level.maze.goals(), &[(3, 1)]);
+        assert_eq!((level.maze.width(), level.maze.height()), (5, 3));
+        // Sin ángulo explícito mira hacia el pasillo libre, al este
+        assert_eq!(level.spawn_angle, 0.0);
+    }
+
+    #[test]
+    fn spawn_directive_overrides_marker() {
+        let level = Level::parse(&format!("@spawn 2.5 1.5 90\n{SMALL}")).unwrap();
+        assert_eq!(level.spawn, Vector2::new(2.5, 1.5));
+        assert_eq!(level.spawn_angle, 90.0_f32.to_radians());
+    }
+
+    #[test]
+    fn spawn_directive_must_be_a_number_on_the_map() {
+        for spawn in ["-3 2", "NaN 1.5", "1.5 inf"] {
+            assert!(
+                matches!(
+                    Level::parse(&format!("@spawn {spawn}\n{SMALL}")),
+                    Err(LevelError::InvalidValue { line: 1, .. })
+                ),
+                "@spawn {spawn}"
+            );
+        }
+    }
+
+    #[test]
+    fn spawn_directive_outside_the_grid() {
+        assert!(matches!(
+            Level::parse(&format!("@spawn 9.5 1.5\n{SMALL}")),
+            Err(LevelError::OutsideMap { x: 9, y: 1 })
+        ));
+        assert!(matches!(
+            Level::parse(&format!("@spawn 2.5 3.5\n{SMALL}")),
+            Err(LevelError::OutsideMap { x: 2, y: 3 })
+        ));
+    }
+
+    #[test]
+    fn spawn_directive_inside_a_wall() {
+        assert!(matches!(
+            Level::parse(&format!("@spawn 0.5 1.5\n{SMALL}")),
+            Err(LevelError::InsideWall { x: 0, y: 1 })
+        ));
+    }
+
+    #[test]
+    fn reports_the_line_of_bad_directives() {
+        match Level::parse(&format!("{SMALL}@sky azul\n")) {
+            Err(LevelError::UnknownDirective { line: 6, directive }) => {
+                assert_eq!(directive, "sky")
+            }
+            other => panic!("se esperaba una directiva desconocida: {:?}", other.err()),
+        }
+        assert!(matches!(
+            Level::parse(&format!("@par rápido\n{SMALL}")),
+            Err(LevelError::InvalidValue { line: 1, .. })
+        ));
+        assert!(matches!(
+            Level::parse("@name\n"),
+            Err(LevelError::MissingValue { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn items_must_be_on_free_cells() {
+        for item in ["@coin 0 0", "@key 500 500 red", "@coin 99 1"] {
+            assert!(
+                matches!(
+                    Level::parse(&format!("{item}\n{SMALL}")),
+                    Err(LevelError::InsideWall { .. })
+                ),
+                "{item}"
+            );
+        }
+        assert!(Level::parse(&format!("@coin 2 1\n{SMALL}")).is_ok());
+    }
+
+    #[test]
+    fn items_cannot_start_under_the_player() {
+        assert!(matches!(
+            Level::parse(&format!("@star 1 1\n{SMALL}")),
+            Err(LevelError::OnSpawn { x: 1, y: 1 })
+        ));
+        let moved = format!("@spawn 2.5 1.5\n@star 1 1\n{SMALL}");
+        assert!(Level::parse(&moved).is_ok());
+    }
+
+    #[test]
+    fn trigger_targets_must_exist() {
+        let cases = [
+            "@trigger 2 1 enter teleport 500 500",
+            "@trigger 2 1 enter teleport 0 0",
+            "@trigger 2 1 9 9 enter message fuera",
+            "@trigger 2 1 enter door 2 1",
+            "@trigger 2 1 enter wall 2 1 #",
+            "@trigger 2 1 enter wall 0 0 Z",
+        ];
+        for trigger in cases {
+            let result = Level::parse(&format!("{trigger}\n{SMALL}"));
+            assert!(result.is_err(), "{trigger}");
+        }
+        assert!(matches!(
+            Level::parse(&format!("{}\n{SMALL}", cases[0])),
+            Err(LevelError::OutsideMap { x: 500, y: 500 })
+        ));
+        let fine = "@trigger 1 1 3 1 exit teleport 2 1 90\n@trigger 2 1 use wall 0 0 -";
+        let level = Level::parse(&format!("{fine}\n{SMALL}")).unwrap();
+        assert_eq!(level.triggers.len(), 2);
+    }
+
+    #[test]
+    fn bundled_levels_load() {
+        for path in ["maze.txt", "mazetky.txt"] {
+            if let Err(e) = Level::load(path) {
+                panic!("{path}: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn requires_a_spawn() {
+        assert!(matches!(
+            Level::parse("#####\n#  G#\n#####\n"),
+            Err(LevelError::Maze(MazeError::MissingSpawn))
+        ));
+    }
+}
@@ -1,27 +1,385 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use raylib::prelude::*;
+use std::fmt;
+use crate::pathfinding;
+use crate::textures::{TextureImage, TextureSet};
+
+pub mod generate;
+
+// Capa de texturas de suelo o techo: una letra por celda o un valor por defecto
+#[derive(Default)]
+pub struct Layer {
+    pub default: Option<char>,
+    pub cells: Vec<Vec<char>>,
+}
+
+impl Layer {
+    fn get(&self, x: i32, y: i32) -> Option<char> {
+        if x >= 0 && y >= 0 {
+            if let Some(&ch) = self
+                .cells
+                .get(y as usize)
+                .and_then(|row| row.get(x as usize))
+            {
+                if ch != ' ' {
+                    return Some(ch);
+                }
+            }
+        }
+        self.default
+    }
+}
+
+// Marcadores de la cuadrícula: se guardan aparte y la celda queda libre
+pub const SPAWN_MARKER: char = 'P';
+pub const GOAL_MARKER: char = 'G';
+// Puerta: se guarda en la cuadrícula como pared, y su estado va aparte (door::Doors)
+pub const DOOR_TILE: char = 'D';
+
+// Casillas que dañan al jugador. Se caminan como el suelo: se guardan aparte,
+// la celda queda libre y el suelo se dibuja con la textura de su letra.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hazard {
+    Lava,
+    Spikes,
+}
+
+impl Hazard {
+    pub const ALL: [Hazard; 2] = [Hazard::Lava, Hazard::Spikes];
+
+    // Letra en la cuadrícula
+    pub fn tile(self) -> char {
+        match self {
+            Hazard::Lava => '~',
+            Hazard::Spikes => '^',
+        }
+    }
+
+    pub fn from_tile(ch: char) -> Option<Hazard> {
+        Hazard::ALL.into_iter().find(|h| h.tile() == ch)
+    }
+
+    // Nombre en pantalla
+    pub fn label(self) -> &'static str {
+        match self {
+            Hazard::Lava => "la lava",
+            Hazard::Spikes => "los pinchos",
+        }
+    }
+
+    // Daño por segundo parado encima
+    pub fn damage(self) -> f32 {
+        match self {
+            Hazard::Lava => 40.0,
+            Hazard::Spikes => 15.0,
+        }
+    }
+}
+
+// Problemas de un laberinto; línea y columna empiezan en 1 como en el editor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MazeError {
+    UnknownTile {
+        line: usize,
+        column: usize,
+        tile: char,
+    },
+    OpenBorder {
+        line: usize,
+        column: usize,
+    },
+    RaggedRow {
+        line: usize,
+        width: usize,
+        expected: usize,
+    },
+    MissingSpawn,
+    MissingGoal,
+    UnreachableGoal {
+        line: usize,
+        column: usize,
+    },
+    DoorWithoutFrame {
+        line: usize,
+        column: usize,
+    },
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MazeError::UnknownTile { line, column, tile } => {
+                write!(
+                    f,
+                    "línea {line}, columna {column}: casilla desconocida '{tile}'"
+                )
+            }
+            MazeError::OpenBorder { line, column } => {
+                write!(
+                    f,
+                    "línea {line}, columna {column}: el borde del mapa está abierto"
+                )
+            }
+            MazeError::RaggedRow {
+                line,
+                width,
+                expected,
+            } => write!(
+                f,
+                "línea {line}: la fila tiene {width} casillas y se esperaban {expected}"
+            ),
+            MazeError::MissingSpawn => {
+                write!(f, "el nivel no tiene punto de inicio (marca P o @spawn)")
+            }
+            MazeError::MissingGoal => write!(f, "el nivel no tiene meta (marca G o @goal)"),
+            MazeError::UnreachableGoal { line, column } => {
+                write!(
+                    f,
+                    "línea {line}, columna {column}: la meta no se puede alcanzar"
+                )
+            }
+            MazeError::DoorWithoutFrame { line, column } => {
+                write!(
+                    f,
+                    "línea {line}, columna {column}: la puerta necesita paredes a ambos lados"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for MazeError {}
 
 pub struct Maze {
     grid: Vec<Vec<char>>,
+    width: usize, // La fila más larga; las filas no cambian de largo
+    floor: Layer,
+    ceiling: Layer,
+    spawn: Option<(usize, usize)>,
+    goals: Vec<(usize, usize)>,
+    hazards: Vec<Vec<Option<Hazard>>>, // Casillas peligrosas, fila por fila
+    lines: Vec<usize>,                 // Línea del archivo de cada fila, para los errores
 }
 
 impl Maze {
-    pub fn load(filename: &str) -> std::io::Result<Maze> {
-        let file = File::open(filename)?;
-        let reader = BufReader::new(file);
-
-        let grid: Vec<Vec<char>> = reader
-            .lines()
-            .filter_map(Result::ok)
-            .map(|line| line.chars().collect())
+    pub fn new(mut grid: Vec<Vec<char>>, floor: Layer, ceiling: Layer) -> Maze {
+        let mut spawn = None;
+        let mut goals = Vec::new();
+        let hazards = grid
+            .iter()
+            .map(|row| row.iter().map(|&ch| Hazard::from_tile(ch)).collect())
             .collect();
+        for (y, row) in grid.iter_mut().enumerate() {
+            for (x, ch) in row.iter_mut().enumerate() {
+                match *ch {
+                    SPAWN_MARKER => spawn = Some((x, y)),
+                    GOAL_MARKER => goals.push((x, y)),
+                    _ if Hazard::from_tile(*ch).is_some() => {}
+                    _ => continue,
+                }
+                *ch = ' ';
+            }
+        }
+
+        let width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+        let lines = (1..=grid.len()).collect();
+        Maze {
+            grid,
+            width,
+            floor,
+            ceiling,
+            spawn,
+            goals,
+            hazards,
+            lines,
+        }
+    }
+
+    // Indica en qué línea del archivo está cada fila de la cuadrícula
+    pub fn set_source_lines(&mut self, lines: Vec<usize>) {
+        self.lines = lines;
+    }
+
+    pub fn set_spawn(&mut self, cell: (usize, usize)) {
+        self.spawn = Some(cell);
+    }
+
+    pub fn add_goal(&mut self, cell: (usize, usize)) {
+        self.goals.push(cell);
+    }
+
+    pub fn spawn(&self) -> Option<(usize, usize)> {
+        self.spawn
+    }
+
+    pub fn goals(&self) -> &[(usize, usize)] {
+        &self.goals
+    }
+
+    pub fn hazard(&self, x: i32, y: i32) -> Option<Hazard> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+        *self.hazards.get(y as usize)?.get(x as usize)?
+    }
+
+    // Revisa que el mapa se pueda jugar; `tiles` son las paredes con textura
+    pub fn validate(&self, tiles: &[char]) -> Result<(), MazeError> {
+        let expected = self.grid.first().map_or(0, |row| row.len());
+        for (y, row) in self.grid.iter().enumerate() {
+            if row.len() != expected {
+                return Err(MazeError::RaggedRow {
+                    line: self.line(y),
+                    width: row.len(),
+                    expected,
+                });
+            }
+            for (x, &ch) in row.iter().enumerate() {
+                if ch != ' ' && !tiles.contains(&ch) {
+                    return Err(MazeError::UnknownTile {
+                        line: self.line(y),
+                        column: x + 1,
+                        tile: ch,
+                    });
+                }
+                let border = x == 0 || y == 0 || x + 1 == expected || y + 1 == self.grid.len();
+                if border && ch == ' ' {
+                    return Err(MazeError::OpenBorder {
+                        line: self.line(y),
+                        column: x + 1,
+                    });
+                }
+            }
+        }
+
+        // Cada puerta va entre dos paredes, para saber hacia dónde se desliza
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, &ch) in row.iter().enumerate() {
+                if ch == DOOR_TILE && self.door_frame((x, y)).is_none() {
+                    return Err(MazeError::DoorWithoutFrame {
+                        line: self.line(y),
+                        column: x + 1,
+                    });
+                }
+            }
+        }
+
+        let spawn = self.spawn.ok_or(MazeError::MissingSpawn)?;
+        if self.goals.is_empty() {
+            return Err(MazeError::MissingGoal);
+        }
+
+        // La meta tiene que estar en la misma región conectada que el inicio
+        let components = pathfinding::connected_components(self);
+        let start = components.label(spawn);
+        for &(x, y) in &self.goals {
+            if start.is_none() || components.label((x, y)) != start {
+                return Err(MazeError::UnreachableGoal {
+                    line: self.line(y),
+                    column: x + 1,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // Orientación de la puerta en `cell`: Some(true) si las paredes están arriba y
+    // abajo (la hoja se cruza avanzando en x), Some(false) si están a los lados
+    pub fn door_frame(&self, (x, y): (usize, usize)) -> Option<bool> {
+        let (x, y) = (x as i32, y as i32);
+        let is_frame = |x, y| self.get_cell(x, y).is_some_and(|ch| ch != DOOR_TILE);
+        if is_frame(x, y - 1) && is_frame(x, y + 1) {
+            Some(true)
+        } else if is_frame(x - 1, y) && is_frame(x + 1, y) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_door(&self, x: i32, y: i32) -> bool {
+        self.get_cell(x, y) == Some(DOOR_TILE)
+    }
+
+    fn line(&self, row: usize) -> usize {
+        self.lines.get(row).copied().unwrap_or(row + 1)
+    }
+
+    // Vuelve a escribir el laberinto en el formato de los archivos de nivel.
+    // Las capas por celda van al final: sus filas siguen hasta la próxima directiva.
+    pub fn to_text(&self) -> String {
+        let layers = [("floor", &self.floor), ("ceiling", &self.ceiling)];
+        let mut text = String::new();
+        for (key, layer) in layers {
+            if let Some(ch) = layer.default {
+                text += &format!("@{key} {ch}\n");
+            }
+        }
+
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, &ch) in row.iter().enumerate() {
+                text.push(if self.spawn == Some((x, y)) {
+                    SPAWN_MARKER
+                } else if self.goals.contains(&(x, y)) {
+                    GOAL_MARKER
+                } else if let Some(hazard) = self.hazard(x as i32, y as i32) {
+                    hazard.tile()
+                } else {
+                    ch
+                });
+            }
+            text.push('\n');
+        }
+
+        for (key, layer) in layers {
+            if !layer.cells.is_empty() {
+                text += &format!("@{key}\n");
+                for row in &layer.cells {
+                    text.extend(row);
+                    text.push('\n');
+                }
+            }
+        }
+        text
+    }
+
+    pub fn floor_texture(&self, x: i32, y: i32) -> Option<char> {
+        match self.hazard(x, y) {
+            Some(hazard) => Some(hazard.tile()),
+            None => self.floor.get(x, y),
+        }
+    }
+
+    pub fn ceiling_texture(&self, x: i32, y: i32) -> Option<char> {
+        self.ceiling.get(x, y)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
 
-        Ok(Maze { grid })
+    pub fn height(&self) -> usize {
+        self.grid.len()
     }
 
     pub fn get_wall(&self, x: f32, y: f32) -> Option<char> {
-        let xi = x as usize;
-        let yi = y as usize;
+        self.get_cell(x.floor() as i32, y.floor() as i32)
+    }
+
+    // Cambia la textura de una pared; las celdas vacías quedan como están
+    pub fn set_wall(&mut self, (x, y): (usize, usize), ch: char) {
+        if let Some(cell) = self.grid.get_mut(y).and_then(|row| row.get_mut(x)) {
+            if *cell != ' ' {
+                *cell = ch;
+            }
+        }
+    }
+
+    // Pared en la celda (x, y); None si está vacía o fuera del mapa
+    pub fn get_cell(&self, x: i32, y: i32) -> Option<char> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+        let (xi, yi) = (x as usize, y as usize);
         if yi < self.grid.len() && xi < self.grid[yi].len() {
             let ch = self.grid[yi][xi];
             if ch != ' ' {
@@ -34,3 +392,108 @@ impl Maze {
         }
     }
 }
+
+// Cada casilla que daña pone su textura bajo su propia letra
+pub fn load_textures(set: &mut TextureSet) {
+    for hazard in Hazard::ALL {
+        set.insert(hazard.tile(), hazard_image(hazard));
+    }
+}
+
+// Suelo de las casillas que dañan: lava con vetas o una placa con pinchos
+fn hazard_image(hazard: Hazard) -> TextureImage {
+    TextureImage::generate(64, |x, y| match hazard {
+        Hazard::Lava => {
+            let wave = ((x as f32 * 0.3).sin() + (y as f32 * 0.2).cos()) * 40.0;
+            Color::new(220, (90.0 + wave) as u8, 20, 255)
+        }
+        Hazard::Spikes => {
+            // Una pirámide por cada cuadro de 16 pixeles
+            let (sx, sy) = ((x % 16) as i32 - 8, (y % 16) as i32 - 8);
+            let shade = 8 - sx.abs().max(sy.abs());
+            let light = (110 + shade * 15) as u8;
+            Color::new(light, light, light + 10, 255)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TILES: [char; 2] = ['#', DOOR_TILE];
+
+    fn maze(rows: &[&str]) -> Maze {
+        let grid = rows.iter().map(|row| row.chars().collect()).collect();
+        Maze::new(grid, Layer::default(), Layer::default())
+    }
+
+    #[test]
+    fn accepts_a_closed_reachable_maze() {
+        let maze = maze(&["#####", "#P#G#", "# D #", "#####"]);
+        assert_eq!(maze.validate(&TILES), Ok(()));
+    }
+
+    #[test]
+    fn rejects_unknown_tiles_and_open_borders() {
+        let unknown = maze(&["#####", "#PXG#", "#####"]);
+        assert_eq!(
+            unknown.validate(&TILES),
+            Err(MazeError::UnknownTile {
+                line: 2,
+                column: 3,
+                tile: 'X'
+            })
+        );
+        let open = maze(&["#####", " P G#", "#####"]);
+        assert_eq!(
+            open.validate(&TILES),
+            Err(MazeError::OpenBorder { line: 2, column: 1 })
+        );
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        let ragged = maze(&["#####", "#P G#", "####"]);
+        assert_eq!(
+            ragged.validate(&TILES),
+            Err(MazeError::RaggedRow {
+                line: 3,
+                width: 4,
+                expected: 5
+            })
+        );
+    }
+
+    #[test]
+    fn requires_spawn_and_reachable_goal() {
+        let no_spawn = maze(&["#####", "#  G#", "#####"]);
+        assert_eq!(no_spawn.validate(&TILES), Err(MazeError::MissingSpawn));
+        let no_goal = maze(&["#####", "#P  #", "#####"]);
+        assert_eq!(no_goal.validate(&TILES), Err(MazeError::MissingGoal));
+        let walled = maze(&["#####", "#P#G#", "#####"]);
+        assert_eq!(
+            walled.validate(&TILES),
+            Err(MazeError::UnreachableGoal { line: 2, column: 4 })
+        );
+    }
+
+    #[test]
+    fn doors_need_a_frame() {
+        let loose = maze(&["######", "#P  G#", "# D  #", "#    #", "######"]);
+        assert_eq!(
+            loose.validate(&TILES),
+            Err(MazeError::DoorWithoutFrame { line: 3, column: 3 })
+        );
+    }
+
+    #[test]
+    fn source_lines_point_into_the_file() {
+        let mut ragged = maze(&["#####", "#P G#", "####"]);
+        ragged.set_source_lines(vec![4, 5, 6]);
+        assert!(matches!(
+            ragged.validate(&TILES),
+            Err(MazeError::RaggedRow { line: 6, .. })
+        ));
+    }
+}
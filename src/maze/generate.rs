@@ -0,0 +1,532 @@
+use super::{Layer, Maze};
+use crate::pathfinding::{self, Cell};
+
+// Algoritmos disponibles para crear laberintos
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Backtracker,
+    Prim,
+    Kruskal,
+    Eller,
+    Rooms,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 5] = [
+        Algorithm::Backtracker,
+        Algorithm::Prim,
+        Algorithm::Kruskal,
+        Algorithm::Eller,
+        Algorithm::Rooms,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Backtracker => "backtracker",
+            Algorithm::Prim => "prim",
+            Algorithm::Kruskal => "kruskal",
+            Algorithm::Eller => "eller",
+            Algorithm::Rooms => "rooms",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Algorithm> {
+        Algorithm::ALL.into_iter().find(|a| a.name() == name)
+    }
+}
+
+// El tamaño se mide en celdas lógicas: el mapa final mide 2 * ancho + 1 casillas,
+// con una pared entre cada par de celdas
+pub struct Options {
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    pub walls: Vec<(char, u32)>, // Letra de pared y su peso relativo
+    pub floor: Option<char>,
+    pub ceiling: Option<char>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            width: 10,
+            height: 10,
+            seed: 0,
+            walls: vec![('#', 6), ('+', 1), ('-', 1), ('|', 1)],
+            floor: Some('|'),
+            ceiling: None,
+        }
+    }
+}
+
+// Genera un laberinto con el inicio y la meta lo más lejos posible entre sí
+pub fn generate(algorithm: Algorithm, options: &Options) -> Maze {
+    let mut rng = Rng::new(options.seed);
+    let mut cells = Cells::new(options.width.max(2), options.height.max(2));
+
+    match algorithm {
+        Algorithm::Backtracker => backtracker(&mut cells, &mut rng),
+        Algorithm::Prim => prim(&mut cells, &mut rng),
+        Algorithm::Kruskal => kruskal(&mut cells, &mut rng),
+        Algorithm::Eller => eller(&mut cells, &mut rng),
+        Algorithm::Rooms => rooms(&mut cells, &mut rng),
+    }
+
+    let grid = cells
+        .open
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&open| {
+                    if open {
+                        ' '
+                    } else {
+                        pick_wall(&options.walls, &mut rng)
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    let floor = Layer {
+        default: options.floor,
+        cells: Vec::new(),
+    };
+    let ceiling = Layer {
+        default: options.ceiling,
+        cells: Vec::new(),
+    };
+    let mut maze = Maze::new(grid, floor, ceiling);
+    let (spawn, goal) = farthest_pair(&maze);
+    maze.set_spawn(spawn);
+    maze.add_goal(goal);
+    maze
+}
+
+fn pick_wall(walls: &[(char, u32)], rng: &mut Rng) -> char {
+    let total: u32 = walls.iter().map(|&(_, weight)| weight).sum();
+    if total == 0 {
+        return '#';
+    }
+    let mut roll = rng.below(total as usize) as u32;
+    for &(ch, weight) in walls {
+        if roll < weight {
+            return ch;
+        }
+        roll -= weight;
+    }
+    '#'
+}
+
+// Cuadrícula de casillas abiertas; la celda lógica (x, y) es la casilla (2x+1, 2y+1)
+struct Cells {
+    width: usize,
+    height: usize,
+    open: Vec<Vec<bool>>,
+}
+
+impl Cells {
+    fn new(width: usize, height: usize) -> Self {
+        Cells {
+            width,
+            height,
+            open: vec![vec![false; 2 * width + 1]; 2 * height + 1],
+        }
+    }
+
+    fn is_carved(&self, (x, y): (usize, usize)) -> bool {
+        self.open[2 * y + 1][2 * x + 1]
+    }
+
+    fn carve(&mut self, (x, y): (usize, usize)) {
+        self.open[2 * y + 1][2 * x + 1] = true;
+    }
+
+    // Abre las dos celdas y la pared que las separa
+    fn link(&mut self, a: (usize, usize), b: (usize, usize)) {
+        self.carve(a);
+        self.carve(b);
+        self.open[a.1 + b.1 + 1][a.0 + b.0 + 1] = true;
+    }
+
+    fn neighbors(&self, (x, y): (usize, usize)) -> Vec<(usize, usize)> {
+        let mut result = Vec::with_capacity(4);
+        if x > 0 {
+            result.push((x - 1, y));
+        }
+        if y > 0 {
+            result.push((x, y - 1));
+        }
+        if x + 1 < self.width {
+            result.push((x + 1, y));
+        }
+        if y + 1 < self.height {
+            result.push((x, y + 1));
+        }
+        result
+    }
+}
+
+// Búsqueda en profundidad con pila: pasillos largos y pocas bifurcaciones
+fn backtracker(cells: &mut Cells, rng: &mut Rng) {
+    let start = (rng.below(cells.width), rng.below(cells.height));
+    cells.carve(start);
+    let mut stack = vec![start];
+    while let Some(&current) = stack.last() {
+        let options: Vec<_> = cells
+            .neighbors(current)
+            .into_iter()
+            .filter(|&n| !cells.is_carved(n))
+            .collect();
+        if options.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let next = options[rng.below(options.len())];
+        cells.link(current, next);
+        stack.push(next);
+    }
+}
+
+// Prim aleatorio: crece desde una celda eligiendo paredes de la frontera al azar
+fn prim(cells: &mut Cells, rng: &mut Rng) {
+    let start = (rng.below(cells.width), rng.below(cells.height));
+    cells.carve(start);
+    let mut frontier: Vec<_> = cells
+        .neighbors(start)
+        .into_iter()
+        .map(|n| (start, n))
+        .collect();
+    while !frontier.is_empty() {
+        let (from, to) = frontier.swap_remove(rng.below(frontier.len()));
+        if cells.is_carved(to) {
+            continue;
+        }
+        cells.link(from, to);
+        for n in cells.neighbors(to) {
+            if !cells.is_carved(n) {
+                frontier.push((to, n));
+            }
+        }
+    }
+}
+
+// Kruskal: recorre las paredes en orden aleatorio y une conjuntos distintos
+fn kruskal(cells: &mut Cells, rng: &mut Rng) {
+    let (w, h) = (cells.width, cells.height);
+    let mut edges = Vec::new();
+    for y in 0..h {
+        for x in 0..w {
+            if x + 1 < w {
+                edges.push(((x, y), (x + 1, y)));
+            }
+            if y + 1 < h {
+                edges.push(((x, y), (x, y + 1)));
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+
+    let mut sets = DisjointSets::new(w * h);
+    for (a, b) in edges {
+        if sets.union(a.1 * w + a.0, b.1 * w + b.0) {
+            cells.link(a, b);
+        }
+    }
+}
+
+// Eller: fila por fila, recordando solo a qué conjunto pertenece cada columna
+fn eller(cells: &mut Cells, rng: &mut Rng) {
+    let (w, h) = (cells.width, cells.height);
+    let mut row_sets = vec![0; w]; // 0 = sin conjunto todavía
+    let mut next_id = 1;
+
+    for y in 0..h {
+        for set in &mut row_sets {
+            if *set == 0 {
+                *set = next_id;
+                next_id += 1;
+            }
+        }
+        for x in 0..w {
+            cells.carve((x, y));
+        }
+
+        // Uniones horizontales; en la última fila se unen todos los conjuntos
+        let last = y + 1 == h;
+        for x in 0..w - 1 {
+            if row_sets[x] != row_sets[x + 1] && (last || rng.below(2) == 0) {
+                cells.link((x, y), (x + 1, y));
+                let old = row_sets[x + 1];
+                let new = row_sets[x];
+                for set in &mut row_sets {
+                    if *set == old {
+                        *set = new;
+                    }
+                }
+            }
+        }
+        if last {
+            break;
+        }
+
+        // Cada conjunto baja al menos una vez a la fila siguiente
+        let mut next_row = vec![0; w];
+        let mut ids: Vec<usize> = row_sets.clone();
+        ids.sort_unstable();
+        ids.dedup();
+        for id in ids {
+            let mut columns: Vec<usize> = (0..w).filter(|&x| row_sets[x] == id).collect();
+            rng.shuffle(&mut columns);
+            let count = 1 + rng.below(columns.len());
+            for &x in &columns[..count] {
+                cells.link((x, y), (x, y + 1));
+                next_row[x] = id;
+            }
+        }
+        row_sets = next_row;
+    }
+}
+
+// Habitaciones rectangulares unidas en cadena por pasillos en L
+fn rooms(cells: &mut Cells, rng: &mut Rng) {
+    let (w, h) = (cells.width, cells.height);
+    let mut placed: Vec<(usize, usize, usize, usize)> = Vec::new();
+
+    for _ in 0..w * h / 4 {
+        let room_w = (2 + rng.below(2)).min(w);
+        let room_h = (2 + rng.below(2)).min(h);
+        let x = rng.below(w - room_w + 1);
+        let y = rng.below(h - room_h + 1);
+        // Deja al menos una celda libre entre habitaciones
+        let overlaps = placed.iter().any(|&(ox, oy, ow, oh)| {
+            x <= ox + ow && ox <= x + room_w && y <= oy + oh && oy <= y + room_h
+        });
+        if overlaps {
+            continue;
+        }
+        for cy in y..y + room_h {
+            for cx in x..x + room_w {
+                if cx + 1 < x + room_w {
+                    cells.link((cx, cy), (cx + 1, cy));
+                }
+                if cy + 1 < y + room_h {
+                    cells.link((cx, cy), (cx, cy + 1));
+                }
+                cells.carve((cx, cy));
+            }
+        }
+        placed.push((x, y, room_w, room_h));
+    }
+
+    if placed.len() < 2 {
+        // Mapa demasiado chico para habitaciones: un pasillo de punta a punta
+        placed = vec![(0, 0, 1, 1), (w - 1, h - 1, 1, 1)];
+    }
+
+    for pair in placed.windows(2) {
+        let center = |&(x, y, rw, rh): &(usize, usize, usize, usize)| (x + rw / 2, y + rh / 2);
+        let (mut x, mut y) = center(&pair[0]);
+        let (tx, ty) = center(&pair[1]);
+        cells.carve((x, y));
+        while x != tx {
+            let nx = if tx > x { x + 1 } else { x - 1 };
+            cells.link((x, y), (nx, y));
+            x = nx;
+        }
+        while y != ty {
+            let ny = if ty > y { y + 1 } else { y - 1 };
+            cells.link((x, y), (x, ny));
+            y = ny;
+        }
+    }
+}
+
+// Dos recorridos en anchura: el punto más lejano de cualquiera, y el más lejano de ese
+fn farthest_pair(maze: &Maze) -> (Cell, Cell) {
+    let start = (0..maze.height())
+        .flat_map(|y| (0..maze.width()).map(move |x| (x, y)))
+        .find(|&cell| pathfinding::is_walkable(maze, cell))
+        .unwrap_or((1, 1));
+    let farthest_from = |cell: Cell| {
+        pathfinding::distance_field(maze, &[cell])
+            .farthest()
+            .map_or(cell, |(far, _)| far)
+    };
+    let a = farthest_from(start);
+    (a, farthest_from(a))
+}
+
+struct DisjointSets {
+    parent: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(size: usize) -> Self {
+        DisjointSets {
+            parent: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    // Devuelve false si ya estaban en el mismo conjunto
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (ra, rb) = (self.find(a), self.find(b));
+        self.parent[ra] = rb;
+        ra != rb
+    }
+}
+
+// SplitMix64: la misma semilla da el mismo laberinto en cualquier plataforma
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Entero en 0..n
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLS: [char; 4] = ['#', '+', '-', '|'];
+
+    fn options(width: usize, height: usize, seed: u64) -> Options {
+        Options {
+            width,
+            height,
+            seed,
+            ..Default::default()
+        }
+    }
+
+    // Todas las casillas libres están en un mismo componente
+    fn assert_connected(maze: &Maze, algorithm: Algorithm) {
+        let components = pathfinding::connected_components(maze);
+        assert_eq!(components.count, 1, "{}", algorithm.name());
+        let spawn = maze.spawn().unwrap();
+        for y in 0..maze.height() {
+            for x in 0..maze.width() {
+                if pathfinding::is_walkable(maze, (x, y)) {
+                    assert_eq!(
+                        components.label((x, y)),
+                        components.label(spawn),
+                        "{} en {x} {y}",
+                        algorithm.name()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_maze() {
+        for algorithm in Algorithm::ALL {
+            let first = generate(algorithm, &options(8, 6, 42)).to_text();
+            let second = generate(algorithm, &options(8, 6, 42)).to_text();
+            assert_eq!(first, second, "{}", algorithm.name());
+            let other = generate(algorithm, &options(8, 6, 43)).to_text();
+            assert_ne!(first, other, "{}", algorithm.name());
+        }
+    }
+
+    #[test]
+    fn every_algorithm_builds_a_valid_connected_maze() {
+        for algorithm in Algorithm::ALL {
+            for seed in 0..8 {
+                let maze = generate(algorithm, &options(9, 7, seed));
+                assert_eq!((maze.width(), maze.height()), (19, 15));
+                // validate también revisa que el borde esté cerrado y la meta se alcance
+                assert_eq!(maze.validate(&WALLS), Ok(()), "{}", algorithm.name());
+                assert_connected(&maze, algorithm);
+                assert_ne!(maze.spawn(), maze.goals().first().copied());
+            }
+        }
+    }
+
+    #[test]
+    fn tiny_sizes_are_clamped_to_two_cells() {
+        for algorithm in Algorithm::ALL {
+            for (width, height) in [(0, 0), (1, 1), (1, 5), (5, 1)] {
+                let maze = generate(algorithm, &options(width, height, 7));
+                let expected = (2 * width.max(2) + 1, 2 * height.max(2) + 1);
+                assert_eq!((maze.width(), maze.height()), expected);
+                assert_eq!(maze.validate(&WALLS), Ok(()), "{}", algorithm.name());
+                assert_connected(&maze, algorithm);
+            }
+        }
+    }
+
+    #[test]
+    fn splitmix_sequence_is_stable() {
+        // Primer valor de referencia de SplitMix64 con semilla 0
+        let mut rng = Rng::new(0);
+        assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+
+        let mut rng = Rng::new(1234);
+        assert!((0..1000).all(|_| rng.below(7) < 7));
+        let mut items: Vec<usize> = (0..20).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn walls_are_picked_by_weight() {
+        let mut rng = Rng::new(5);
+        assert_eq!(pick_wall(&[], &mut rng), '#');
+        assert_eq!(pick_wall(&[('+', 0)], &mut rng), '#');
+        assert!((0..100).all(|_| pick_wall(&[('+', 0), ('-', 3)], &mut rng) == '-'));
+
+        let walls = Options::default().walls;
+        let picks: Vec<char> = (0..9000).map(|_| pick_wall(&walls, &mut rng)).collect();
+        let count = |ch| picks.iter().filter(|&&c| c == ch).count();
+        // Pesos 6:1:1:1 sobre 9000 tiradas: unas 6000 y 1000 de cada una
+        assert!((5700..6300).contains(&count('#')), "{}", count('#'));
+        for ch in ['+', '-', '|'] {
+            assert!((850..1150).contains(&count(ch)), "{ch}: {}", count(ch));
+        }
+    }
+
+    #[test]
+    fn farthest_pair_spans_the_longest_corridor() {
+        let grid = ["#######", "#     #", "#### ##", "#### ##", "#######"]
+            .iter()
+            .map(|row| row.chars().collect())
+            .collect();
+        let maze = Maze::new(grid, Layer::default(), Layer::default());
+        // Desde la primera celda libre (1, 1), lo más lejos es la punta de abajo,
+        // y desde ahí vuelve a ser (1, 1): 5 pasos, más que el otro extremo (5, 1)
+        let (a, b) = farthest_pair(&maze);
+        assert_eq!((a, b), ((4, 3), (1, 1)));
+        assert_eq!(pathfinding::distance_field(&maze, &[a]).get(b), Some(5));
+    }
+}
@@ -0,0 +1,291 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use crate::maze::Maze;
+
+pub type Cell = (usize, usize);
+
+// Misma regla que la colisión del jugador: se camina por donde no hay pared.
+// Las puertas cuentan como libres porque se pueden abrir. Además la celda debe
+// estar dentro del mapa, para no buscar caminos por fuera.
+pub fn is_walkable(maze: &Maze, (x, y): Cell) -> bool {
+    let (cx, cy) = (x as i32, y as i32);
+    y < maze.height()
+        && x < maze.width()
+        && (maze.get_cell(cx, cy).is_none() || maze.is_door(cx, cy))
+}
+
+// Vecinos caminables en las cuatro direcciones
+pub fn neighbors(maze: &Maze, (x, y): Cell) -> impl Iterator<Item = Cell> + '_ {
+    let candidates = [
+        x.checked_add(1).map(|nx| (nx, y)),
+        x.checked_sub(1).map(|nx| (nx, y)),
+        y.checked_add(1).map(|ny| (x, ny)),
+        y.checked_sub(1).map(|ny| (x, ny)),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .filter(move |&cell| is_walkable(maze, cell))
+}
+
+// Pasos desde la fuente más cercana hasta cada celda; None si no se alcanza
+pub struct DistanceField {
+    width: usize,
+    distances: Vec<Option<u32>>,
+}
+
+impl DistanceField {
+    // Cantidad de celdas alcanzadas
+    pub fn reached(&self) -> usize {
+        self.distances.iter().filter(|d| d.is_some()).count()
+    }
+
+    pub fn get(&self, (x, y): Cell) -> Option<u32> {
+        if x >= self.width {
+            return None;
+        }
+        self.distances.get(y * self.width + x).copied().flatten()
+    }
+
+    // Camino desde `start` bajando de a un paso hasta la fuente más cercana
+    pub fn path_from(&self, maze: &Maze, start: Cell) -> Option<Vec<Cell>> {
+        let mut distance = self.get(start)?;
+        let mut path = vec![start];
+        while distance > 0 {
+            let here = path[path.len() - 1];
+            let next = neighbors(maze, here).find(|&cell| self.get(cell) == Some(distance - 1))?;
+            path.push(next);
+            distance -= 1;
+        }
+        Some(path)
+    }
+
+    // Celda alcanzable más lejana y su distancia
+    pub fn farthest(&self) -> Option<(Cell, u32)> {
+        self.distances
+            .iter()
+            .enumerate()
+            .filter_map(|(i, d)| d.map(|d| ((i % self.width, i / self.width), d)))
+            .max_by_key(|&(_, d)| d)
+    }
+}
+
+// Recorrido en anchura desde varias fuentes a la vez
+pub fn distance_field(maze: &Maze, sources: &[Cell]) -> DistanceField {
+    let width = maze.width();
+    let mut field = DistanceField {
+        width,
+        distances: vec![None; width * maze.height()],
+    };
+
+    let mut pending = VecDeque::new();
+    for &cell in sources {
+        if is_walkable(maze, cell) {
+            field.distances[cell.1 * width + cell.0] = Some(0);
+            pending.push_back(cell);
+        }
+    }
+    while let Some(cell) = pending.pop_front() {
+        let next = field.distances[cell.1 * width + cell.0].unwrap_or(0) + 1;
+        for (nx, ny) in neighbors(maze, cell) {
+            let slot = &mut field.distances[ny * width + nx];
+            if slot.is_none() {
+                *slot = Some(next);
+                pending.push_back((nx, ny));
+            }
+        }
+    }
+    field
+}
+
+// A* con distancia Manhattan; el camino incluye el inicio y el final. No pasa por
+// las celdas `blocked` (por ejemplo las puertas cerradas, para quien no las abre).
+pub fn find_path(
+    maze: &Maze,
+    start: Cell,
+    goal: Cell,
+    blocked: impl Fn(Cell) -> bool,
+) -> Option<Vec<Cell>> {
+    if !is_walkable(maze, start) || !is_walkable(maze, goal) || blocked(goal) {
+        return None;
+    }
+    let width = maze.width();
+    let index = |(x, y): Cell| y * width + x;
+    let heuristic = |(x, y): Cell| (x.abs_diff(goal.0) + y.abs_diff(goal.1)) as u32;
+
+    let mut cost = vec![u32::MAX; width * maze.height()];
+    let mut came_from: Vec<Option<Cell>> = vec![None; cost.len()];
+    let mut open = BinaryHeap::new();
+    cost[index(start)] = 0;
+    open.push(Reverse((heuristic(start), start)));
+
+    while let Some(Reverse((_, cell))) = open.pop() {
+        if cell == goal {
+            let mut path = vec![goal];
+            let mut current = goal;
+            while let Some(previous) = came_from[index(current)] {
+                path.push(previous);
+                current = previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let next_cost = cost[index(cell)] + 1;
+        for next in neighbors(maze, cell).filter(|&next| !blocked(next)) {
+            if next_cost < cost[index(next)] {
+                cost[index(next)] = next_cost;
+                came_from[index(next)] = Some(cell);
+                open.push(Reverse((next_cost + heuristic(next), next)));
+            }
+        }
+    }
+    None
+}
+
+// Regiones caminables conectadas entre sí
+pub struct Components {
+    width: usize,
+    labels: Vec<Option<usize>>,
+    pub count: usize,
+}
+
+impl Components {
+    pub fn label(&self, (x, y): Cell) -> Option<usize> {
+        if x >= self.width {
+            return None;
+        }
+        self.labels.get(y * self.width + x).copied().flatten()
+    }
+}
+
+pub fn connected_components(maze: &Maze) -> Components {
+    let width = maze.width();
+    let mut components = Components {
+        width,
+        labels: vec![None; width * maze.height()],
+        count: 0,
+    };
+
+    for y in 0..maze.height() {
+        for x in 0..width {
+            if components.labels[y * width + x].is_some() || !is_walkable(maze, (x, y)) {
+                continue;
+            }
+            let label = components.count;
+            components.count += 1;
+            components.labels[y * width + x] = Some(label);
+            let mut pending = vec![(x, y)];
+            while let Some(cell) = pending.pop() {
+                for (nx, ny) in neighbors(maze, cell) {
+                    let slot = &mut components.labels[ny * width + nx];
+                    if slot.is_none() {
+                        *slot = Some(label);
+                        pending.push((nx, ny));
+                    }
+                }
+            }
+        }
+    }
+    components
+}
+
+// Camino hasta la meta más cercana siguiendo los pasillos
+pub fn path_to_nearest(maze: &Maze, start: Cell, goals: &[Cell]) -> Option<Vec<Cell>> {
+    distance_field(maze, goals).path_from(maze, start)
+}
+
+// Puntaje de dificultad: largo del camino a la meta más cercana, más un extra por
+// cada bifurcación en el camino (donde el jugador se puede equivocar)
+pub fn difficulty(maze: &Maze, start: Cell, goals: &[Cell]) -> Option<u32> {
+    let path = path_to_nearest(maze, start, goals)?;
+    let forks = path
+        .iter()
+        .filter(|&&cell| neighbors(maze, cell).count() > 2)
+        .count() as u32;
+    Some(path.len() as u32 - 1 + forks * 3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::Layer;
+
+    fn maze(rows: &[&str]) -> Maze {
+        let grid = rows.iter().map(|row| row.chars().collect()).collect();
+        Maze::new(grid, Layer::default(), Layer::default())
+    }
+
+    #[test]
+    fn finds_the_shortest_path_around_walls() {
+        let maze = maze(&["#####", "#   #", "# # #", "#   #", "#####"]);
+        let path = find_path(&maze, (1, 1), (3, 3), |_| false).unwrap();
+        assert_eq!(path.first(), Some(&(1, 1)));
+        assert_eq!(path.last(), Some(&(3, 3)));
+        assert_eq!(path.len(), 5);
+        // Cada paso va a una celda vecina y libre
+        for pair in path.windows(2) {
+            let ((ax, ay), (bx, by)) = (pair[0], pair[1]);
+            assert_eq!(ax.abs_diff(bx) + ay.abs_diff(by), 1);
+            assert!(is_walkable(&maze, pair[1]));
+        }
+        let still = find_path(&maze, (1, 1), (1, 1), |_| false);
+        assert_eq!(still, Some(vec![(1, 1)]));
+    }
+
+    #[test]
+    fn no_path_into_walls_or_closed_regions() {
+        let maze = maze(&["#####", "# # #", "#####"]);
+        assert_eq!(find_path(&maze, (1, 1), (3, 1), |_| false), None);
+        assert_eq!(find_path(&maze, (1, 1), (2, 1), |_| false), None);
+        assert_eq!(find_path(&maze, (1, 1), (9, 9), |_| false), None);
+    }
+
+    #[test]
+    fn doors_count_as_walkable() {
+        let maze = maze(&["#####", "# D #", "#####"]);
+        let path = find_path(&maze, (1, 1), (3, 1), |_| false);
+        assert_eq!(path, Some(vec![(1, 1), (2, 1), (3, 1)]));
+    }
+
+    #[test]
+    fn avoids_blocked_cells() {
+        let square = maze(&["#####", "#   #", "# D #", "#   #", "#####"]);
+        let door = |cell: Cell| cell == (2, 2);
+        let path = find_path(&square, (2, 1), (2, 3), door).unwrap();
+        assert_eq!(path.len(), 5);
+        assert!(!path.contains(&(2, 2)));
+
+        // La puerta es el único paso
+        let corridor = maze(&["#####", "# D #", "#####"]);
+        let door = |cell: Cell| cell == (2, 1);
+        assert_eq!(find_path(&corridor, (1, 1), (3, 1), door), None);
+    }
+
+    #[test]
+    fn labels_connected_components() {
+        let maze = maze(&["#######", "#  #  #", "#  #  #", "#######"]);
+        let components = connected_components(&maze);
+        assert_eq!(components.count, 2);
+        assert_eq!(components.label((1, 1)), components.label((2, 2)));
+        assert_ne!(components.label((1, 1)), components.label((4, 1)));
+        assert_eq!(components.label((3, 1)), None);
+        assert_eq!(components.label((40, 1)), None);
+    }
+
+    #[test]
+    fn distance_field_from_several_sources() {
+        let maze = maze(&["#######", "#     #", "#######"]);
+        let field = distance_field(&maze, &[(1, 1), (5, 1)]);
+        assert_eq!(field.reached(), 5);
+        assert_eq!(field.farthest(), Some(((3, 1), 2)));
+    }
+
+    #[test]
+    fn walks_down_to_the_nearest_goal() {
+        let maze = maze(&["#######", "#     #", "# ### #", "#     #", "#######"]);
+        let path = path_to_nearest(&maze, (2, 1), &[(5, 3), (1, 3)]).unwrap();
+        assert_eq!(path, vec![(2, 1), (1, 1), (1, 2), (1, 3)]);
+        assert_eq!(path_to_nearest(&maze, (3, 2), &[(1, 1)]), None);
+    }
+}
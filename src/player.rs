@@ -1,108 +1,182 @@
-use raylib::prelude::*;
-use crate::maze::Maze;
+use crate::input::InputState;
+use crate::item::{Inventory, Item};
+use crate::weapon::{self, Weapon};
+use crate::world::Transform;
 
+pub const MAX_HEALTH: f32 = 100.0;
+pub const MAX_ARMOR: f32 = 100.0;
+const ARMOR_ABSORB: f32 = 2.0 / 3.0; // Parte del daño que para la armadura
+const HEALTH_PICKUP: f32 = 25.0;
+const ARMOR_PICKUP: f32 = 50.0;
+pub const RADIUS: f32 = 0.2; // Radio de colisión, en celdas
+
+// Estado y controles del jugador; su cuerpo (posición, ángulo, velocidad y radio)
+// es una entidad más del mundo
 pub struct Player {
-    pub pos: Vector2,
-    pub a: f32,
     pub fov: f32,
-    speed: f32,
-    rot_speed: f32,
-    collision_radius: f32,
+    pub acceleration: f32,      // Celdas por segundo², al mantener una tecla
+    pub friction: f32,          // Fracción de la velocidad que se pierde por segundo
+    pub max_speed: f32,         // Celdas por segundo
+    pub rot_speed: f32,         // Radianes por segundo con el teclado
+    pub mouse_sensitivity: f32, // Radianes por pixel
+    pub health: f32,            // 0..MAX_HEALTH; en 0 se termina la partida
+    pub armor: f32,             // 0..MAX_ARMOR
+    pub inventory: Inventory,
+    pub weapon: Weapon,
 }
 
-impl Player {
-    pub fn new() -> Self {
+impl Default for Player {
+    fn default() -> Self {
         Player {
-            pos: Vector2 { x: 3.5, y: 3.5 },
-            a: 0.0,
             fov: std::f32::consts::FRAC_PI_3, // 60 grados
-            speed: 20.0,
-            rot_speed: 10.0,
-            collision_radius: 0.2,
+            acceleration: 40.0,
+            friction: 10.0,
+            max_speed: 4.0,
+            rot_speed: 3.0,
+            mouse_sensitivity: 0.003,
+            health: MAX_HEALTH,
+            armor: 0.0,
+            inventory: Inventory::default(),
+            weapon: Weapon::default(),
         }
     }
+}
 
-    pub fn update(&mut self, rl: &RaylibHandle, maze: &Maze) {
-        let dt = 1.0 / 60.0;
+impl Player {
+    // La armadura se gasta absorbiendo parte del daño; el resto va a la salud
+    pub fn damage(&mut self, amount: f32) {
+        let absorbed = (amount * ARMOR_ABSORB).min(self.armor);
+        self.armor -= absorbed;
+        self.health = (self.health - (amount - absorbed)).max(0.0);
+    }
 
-        // Rotación
-        if rl.is_key_down(KeyboardKey::KEY_A) {
-            self.a -= self.rot_speed * dt;
-        }
-        if rl.is_key_down(KeyboardKey::KEY_D) {
-            self.a += self.rot_speed * dt;
-        }
+    pub fn is_dead(&self) -> bool {
+        self.health <= 0.0
+    }
 
-        // Rotación con el mouse
-        let mouse_delta = rl.get_mouse_delta();
-        if mouse_delta.x != 0.0 {
-            self.a += mouse_delta.x * 0.003;
+    // Las llaves van al inventario; botiquines, armaduras y balas se usan enseguida
+    pub fn pick_up(&mut self, item: Item) {
+        match item {
+            Item::Health => self.health = (self.health + HEALTH_PICKUP).min(MAX_HEALTH),
+            Item::Armor => self.armor = (self.armor + ARMOR_PICKUP).min(MAX_ARMOR),
+            Item::Ammo => self.weapon.ammo += weapon::AMMO_PICKUP,
+            _ if item.is_kept() => self.inventory.add(item),
+            _ => {}
         }
+    }
 
-        // Movimiento frontal
-        let mut dx = 0.0;
-        let mut dy = 0.0;
+    // El mouse ya trae el desplazamiento de todo el cuadro: se aplica una vez por cuadro
+    pub fn look(&self, input: &InputState, body: &mut Transform) {
+        body.angle += input.look * self.mouse_sensitivity;
+    }
 
-        if rl.is_key_down(KeyboardKey::KEY_W) {
-            dx += self.a.cos();
-            dy += self.a.sin();
-        }
-        if rl.is_key_down(KeyboardKey::KEY_S) {
-            dx -= self.a.cos();
-            dy -= self.a.sin();
-        }
+    // Un paso de simulación de `dt` segundos: gira el cuerpo y ajusta su velocidad;
+    // el sistema de colisiones lo mueve después
+    pub fn update(&self, input: &InputState, body: &mut Transform, dt: f32) {
+        // Rotación
+        body.angle += input.turn * self.rot_speed * dt;
 
-        // Movimiento lateral (strafe)
-        if rl.is_key_down(KeyboardKey::KEY_E) {
-            dx += (self.a + std::f32::consts::FRAC_PI_2).cos();
-            dy += (self.a + std::f32::consts::FRAC_PI_2).sin();
-        }
-        if rl.is_key_down(KeyboardKey::KEY_Q) {
-            dx += (self.a - std::f32::consts::FRAC_PI_2).cos();
-            dy += (self.a - std::f32::consts::FRAC_PI_2).sin();
-        }
+        // Movimiento frontal y lateral (strafe)
+        let right = body.angle + std::f32::consts::FRAC_PI_2;
+        let mut dx = body.angle.cos() * input.forward + right.cos() * input.strafe;
+        let mut dy = body.angle.sin() * input.forward + right.sin() * input.strafe;
 
-        // Normalizar el vector de movimiento si no es cero
-        if dx != 0.0 || dy != 0.0 {
-            let length = (dx * dx + dy * dy).sqrt();
+        // Normalizar en diagonal; con palanca se conserva la intensidad parcial
+        let length = (dx * dx + dy * dy).sqrt();
+        if length > 1.0 {
             dx /= length;
             dy /= length;
         }
 
-        // Calcular nueva posición
-        let new_x = self.pos.x + dx * self.speed * dt;
-        let new_y = self.pos.y + dy * self.speed * dt;
-
-        // Comprobar colisiones en X
-        if !self.check_collision(new_x, self.pos.y, maze) {
-            self.pos.x = new_x;
+        // Acelerar hacia donde se pide, frenar por fricción y limitar la rapidez
+        let velocity = &mut body.velocity;
+        velocity.x += dx * self.acceleration * dt;
+        velocity.y += dy * self.acceleration * dt;
+        let damping = (1.0 - self.friction * dt).max(0.0);
+        velocity.x *= damping;
+        velocity.y *= damping;
+        let speed = velocity.length();
+        if speed > self.max_speed {
+            velocity.x *= self.max_speed / speed;
+            velocity.y *= self.max_speed / speed;
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use raylib::prelude::Vector2;
+
+    const DT: f32 = 1.0 / 120.0;
 
-        // Comprobar colisiones en Y
-        if !self.check_collision(self.pos.x, new_y, maze) {
-            self.pos.y = new_y;
+    fn run(player: &Player, input: &InputState, seconds: f32) -> Transform {
+        let mut body = Transform::new(Vector2::zero(), 0.0);
+        for _ in 0..(seconds / DT) as usize {
+            player.update(input, &mut body, DT);
         }
+        body
     }
 
-    fn check_collision(&self, x: f32, y: f32, maze: &Maze) -> bool {
-        // Comprobar colisiones en varios puntos alrededor del jugador
-        let check_points = [
-            (x + self.collision_radius, y),
-            (x - self.collision_radius, y),
-            (x, y + self.collision_radius),
-            (x, y - self.collision_radius),
-            (x + self.collision_radius * 0.7, y + self.collision_radius * 0.7),
-            (x - self.collision_radius * 0.7, y + self.collision_radius * 0.7),
-            (x + self.collision_radius * 0.7, y - self.collision_radius * 0.7),
-            (x - self.collision_radius * 0.7, y - self.collision_radius * 0.7),
-        ];
-
-        for (check_x, check_y) in check_points.iter() {
-            if maze.get_wall(*check_x, *check_y).is_some() {
-                return true;
-            }
+    #[test]
+    fn accelerates_forward_up_to_max_speed() {
+        let input = InputState {
+            forward: 1.0,
+            ..Default::default()
+        };
+        let mut player = Player::default();
+        let early = run(&player, &input, 0.05);
+        assert!(early.velocity.x > 0.0 && early.velocity.x < player.max_speed);
+        assert!(early.velocity.y.abs() < 1e-4);
+
+        // Con mucha aceleración manda el tope de velocidad
+        player.acceleration = 1000.0;
+        let body = run(&player, &input, 1.0);
+        assert!((body.velocity.x - player.max_speed).abs() < 1e-3);
+    }
+
+    #[test]
+    fn diagonal_is_not_faster() {
+        let player = Player::default();
+        let straight = InputState {
+            forward: 1.0,
+            ..Default::default()
+        };
+        let diagonal = InputState {
+            strafe: 1.0,
+            ..straight.clone()
+        };
+        let expected = run(&player, &straight, 2.0).velocity.length();
+        let body = run(&player, &diagonal, 2.0);
+        assert!((body.velocity.length() - expected).abs() < 1e-3);
+        // Adelante es +x y la derecha es +y: la mitad exacta
+        assert!((body.velocity.x - body.velocity.y).abs() < 1e-3);
+    }
+
+    #[test]
+    fn friction_stops_the_body() {
+        let player = Player::default();
+        let mut body = Transform::new(Vector2::zero(), 0.0);
+        body.velocity = Vector2::new(3.0, 0.0);
+        for _ in 0..120 {
+            player.update(&InputState::default(), &mut body, DT);
         }
+        assert!(body.velocity.length() < 1e-3);
+    }
 
-        false
+    #[test]
+    fn turning_and_mouse_look() {
+        let player = Player::default();
+        let input = InputState {
+            turn: 1.0,
+            look: 100.0,
+            ..Default::default()
+        };
+        let mut body = Transform::new(Vector2::zero(), 0.0);
+        player.update(&input, &mut body, 0.5);
+        assert!((body.angle - player.rot_speed * 0.5).abs() < 1e-5);
+        player.look(&input, &mut body);
+        let expected = player.rot_speed * 0.5 + 100.0 * player.mouse_sensitivity;
+        assert!((body.angle - expected).abs() < 1e-5);
     }
 }
@@ -0,0 +1,127 @@
+use raylib::prelude::*;
+use crate::door::Doors;
+use crate::maze::Maze;
+
+// Cara de la celda contra la que chocó el rayo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    North,
+    South,
+    East,
+    West,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RayHit {
+    pub cell: (usize, usize),
+    pub side: Side,
+    pub distance: f32, // Distancia euclidiana desde el origen
+    pub u: f32,        // Coordenada horizontal de textura (0..1)
+    pub wall: char,
+}
+
+// Recorre la cuadrícula celda por celda (DDA) hasta chocar con una pared
+pub fn cast_ray(
+    maze: &Maze,
+    doors: &Doors,
+    origin: Vector2,
+    angle: f32,
+    max_depth: f32,
+) -> Option<RayHit> {
+    let dir_x = angle.cos();
+    let dir_y = angle.sin();
+
+    let mut map_x = origin.x.floor() as i32;
+    let mut map_y = origin.y.floor() as i32;
+
+    // Distancia que recorre el rayo para cruzar una celda completa en cada eje
+    let delta_x = if dir_x == 0.0 { f32::INFINITY } else { (1.0 / dir_x).abs() };
+    let delta_y = if dir_y == 0.0 { f32::INFINITY } else { (1.0 / dir_y).abs() };
+
+    let (step_x, mut side_dist_x) = if dir_x < 0.0 {
+        (-1, (origin.x - map_x as f32) * delta_x)
+    } else {
+        (1, (map_x as f32 + 1.0 - origin.x) * delta_x)
+    };
+    let (step_y, mut side_dist_y) = if dir_y < 0.0 {
+        (-1, (origin.y - map_y as f32) * delta_y)
+    } else {
+        (1, (map_y as f32 + 1.0 - origin.y) * delta_y)
+    };
+
+    let width = maze.width() as i32;
+    let height = maze.height() as i32;
+
+    loop {
+        // Avanzar a la siguiente línea de la cuadrícula más cercana
+        let (distance, side) = if side_dist_x < side_dist_y {
+            let d = side_dist_x;
+            side_dist_x += delta_x;
+            map_x += step_x;
+            (d, if step_x > 0 { Side::West } else { Side::East })
+        } else {
+            let d = side_dist_y;
+            side_dist_y += delta_y;
+            map_y += step_y;
+            (d, if step_y > 0 { Side::North } else { Side::South })
+        };
+
+        if distance > max_depth || map_x < 0 || map_y < 0 || map_x >= width || map_y >= height {
+            return None;
+        }
+
+        if let Some(wall) = maze.get_cell(map_x, map_y) {
+            let cell = (map_x as usize, map_y as usize);
+
+            // Puerta: hoja delgada a media celda de la cara por la que entró el rayo.
+            // Si el rayo sale de la celda antes de llegar a la hoja, o pasa por la parte
+            // ya abierta, sigue de largo y ve el marco del otro lado.
+            let entered_x = matches!(side, Side::West | Side::East);
+            if let Some(door) = doors.get(cell).filter(|door| door.across_x == entered_x) {
+                let (door_distance, exit) = if entered_x {
+                    (distance + delta_x / 2.0, side_dist_y)
+                } else {
+                    (distance + delta_y / 2.0, side_dist_x)
+                };
+                if door_distance > exit || door_distance > max_depth {
+                    continue;
+                }
+                let along = if entered_x {
+                    origin.y + dir_y * door_distance - map_y as f32
+                } else {
+                    origin.x + dir_x * door_distance - map_x as f32
+                };
+                if along < door.open {
+                    continue;
+                }
+                // La textura se desliza junto con la hoja
+                return Some(RayHit {
+                    cell,
+                    side,
+                    distance: door_distance,
+                    u: along - door.open,
+                    wall,
+                });
+            }
+
+            let hit_x = origin.x + dir_x * distance;
+            let hit_y = origin.y + dir_y * distance;
+
+            // Orientar u para que la textura se lea de izquierda a derecha vista de frente
+            let u = match side {
+                Side::North => 1.0 - hit_x.fract(),
+                Side::South => hit_x.fract(),
+                Side::West => hit_y.fract(),
+                Side::East => 1.0 - hit_y.fract(),
+            };
+
+            return Some(RayHit {
+                cell,
+                side,
+                distance,
+                u,
+                wall,
+            });
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use raylib::prelude::*;
+use crate::door::Doors;
+use crate::framebuffer::Framebuffer;
+use crate::maze::Maze;
+use crate::raycaster::{cast_ray, RayHit, Side};
+use crate::sprite::Sprite;
+use crate::textures::TextureSet;
+
+// Punto de vista desde el que se dibuja la escena
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    pub pos: Vector2,
+    pub angle: f32,
+    pub fov: f32,
+}
+
+impl Camera {
+    // Ángulo del rayo que corresponde a la columna `column` de `width`
+    pub fn ray_angle(&self, column: usize, width: usize) -> f32 {
+        let ray_fraction = column as f32 / width as f32;
+        self.angle - (self.fov / 2.0) + (self.fov * ray_fraction)
+    }
+}
+
+// Dibuja la escena en un Framebuffer usando solo datos en CPU (sin ventana)
+pub struct Renderer {
+    pub max_depth: f32,
+    pub wall_scale: f32,
+    pub sky_color: Color,   // Techo sin textura
+    pub floor_color: Color, // Suelo sin textura
+    zbuffer: Vec<f32>,      // Distancia perpendicular a la pared de cada columna
+}
+
+impl Renderer {
+    pub fn new(max_depth: f32) -> Self {
+        Renderer {
+            max_depth,
+            wall_scale: 1.5,
+            sky_color: Color::DARKBLUE,
+            floor_color: Color::DARKGRAY,
+            zbuffer: Vec::new(),
+        }
+    }
+
+    // Devuelve el impacto de cada columna para reutilizarlo (minimapa, etc.)
+    pub fn render(
+        &mut self,
+        maze: &Maze,
+        doors: &Doors,
+        camera: &Camera,
+        textures: &TextureSet,
+        framebuffer: &mut Framebuffer,
+    ) -> Vec<Option<RayHit>> {
+        let width = framebuffer.width();
+        let rows = framebuffer.height();
+        let height = rows as f32;
+
+        framebuffer.clear();
+
+        // Distancia perpendicular al suelo que se ve en cada fila bajo el horizonte
+        let horizon = height / 2.0;
+        let row_distance: Vec<f32> = (0..rows)
+            .map(|y| {
+                let p = y as f32 + 0.5 - horizon;
+                if p > 0.0 {
+                    height * self.wall_scale / 2.0 / p
+                } else {
+                    f32::INFINITY
+                }
+            })
+            .collect();
+
+        self.zbuffer.clear();
+        self.zbuffer.resize(width, f32::INFINITY);
+
+        let mut hits = Vec::with_capacity(width);
+        for i in 0..width {
+            let angle = camera.ray_angle(i, width);
+            let hit = cast_ray(maze, doors, camera.pos, angle, self.max_depth);
+            hits.push(hit);
+
+            // Corrección de ojo de pez: la distancia de fila es perpendicular a la cámara
+            let cos_offset = (camera.angle - angle).cos();
+
+            let (start, end) = match hit {
+                Some(hit) => {
+                    let wall_height = (height * self.wall_scale) / (hit.distance * cos_offset);
+                    (
+                        (horizon - wall_height / 2.0).max(0.0) as usize,
+                        (horizon + wall_height / 2.0).min(height) as usize,
+                    )
+                }
+                None => (rows / 2, rows / 2),
+            };
+
+            // Suelo y techo: la misma posición del mundo vista arriba y abajo del horizonte
+            let dir_x = angle.cos() / cos_offset;
+            let dir_y = angle.sin() / cos_offset;
+            for (y, &distance) in row_distance.iter().enumerate().skip(end.max(rows / 2)) {
+                let world_x = camera.pos.x + dir_x * distance;
+                let world_y = camera.pos.y + dir_y * distance;
+                let (cell_x, cell_y) = (world_x.floor() as i32, world_y.floor() as i32);
+
+                let floor = self.sample_surface(
+                    textures,
+                    maze.floor_texture(cell_x, cell_y),
+                    world_x,
+                    world_y,
+                    self.floor_color,
+                );
+                framebuffer.set(i, y, floor);
+
+                let ceiling_y = rows - 1 - y;
+                if ceiling_y < start {
+                    let ceiling = self.sample_surface(
+                        textures,
+                        maze.ceiling_texture(cell_x, cell_y),
+                        world_x,
+                        world_y,
+                        self.sky_color,
+                    );
+                    framebuffer.set(i, ceiling_y, ceiling);
+                }
+            }
+
+            let Some(hit) = hit else { continue };
+            self.zbuffer[i] = hit.distance * cos_offset;
+            let Some(image) = textures.get(hit.wall) else { continue };
+
+            let wall_height = (height * self.wall_scale) / (hit.distance * cos_offset);
+            let wall_top = horizon - wall_height / 2.0;
+            let tx = (hit.u * image.width as f32) as u32;
+
+            // Sombrear las caras norte/sur para distinguir las esquinas
+            let shade = match hit.side {
+                Side::North | Side::South => 0.8,
+                Side::East | Side::West => 1.0,
+            };
+
+            for y in start..end {
+                let ty = ((y as f32 - wall_top) / wall_height * image.height as f32) as u32;
+                let color = image.get_pixel_color(tx, ty);
+                framebuffer.set(i, y, shade_color(color, shade));
+            }
+        }
+
+        hits
+    }
+
+    // Dibuja los billboards de lejos a cerca, ocultos por las paredes del z-buffer.
+    // Debe llamarse después de render() con la misma cámara.
+    pub fn render_sprites(
+        &self,
+        sprites: &[(Vector2, &Sprite)],
+        camera: &Camera,
+        textures: &TextureSet,
+        framebuffer: &mut Framebuffer,
+    ) {
+        let width = framebuffer.width();
+        let height = framebuffer.height() as f32;
+        let horizon = height / 2.0;
+
+        // (distancia perpendicular, ángulo relativo a la cámara, sprite)
+        let mut visible: Vec<(f32, f32, &Sprite)> = sprites
+            .iter()
+            .filter_map(|&(pos, sprite)| {
+                let dx = pos.x - camera.pos.x;
+                let dy = pos.y - camera.pos.y;
+                let mut relative = dy.atan2(dx) - camera.angle;
+                relative = (relative + PI as f32).rem_euclid(2.0 * PI as f32) - PI as f32;
+                let distance = (dx * dx + dy * dy).sqrt() * relative.cos();
+                (distance > 0.1).then_some((distance, relative, sprite))
+            })
+            .collect();
+        visible.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        for (distance, relative, sprite) in visible {
+            let Some(image) = textures.sprite(sprite.image_name()) else { continue };
+
+            let wall_height = height * self.wall_scale / distance;
+            let sprite_height = wall_height * sprite.scale;
+            let sprite_width = sprite_height * image.width as f32 / image.height as f32;
+
+            // Las columnas están repartidas uniformemente en ángulo
+            let center_x = (relative + camera.fov / 2.0) / camera.fov * width as f32;
+            let left = center_x - sprite_width / 2.0;
+            // Apoyado en el suelo
+            let bottom = horizon + wall_height / 2.0;
+            let top = bottom - sprite_height;
+
+            let x_start = left.max(0.0) as usize;
+            let x_end = (left + sprite_width).clamp(0.0, width as f32) as usize;
+            let y_start = top.max(0.0) as usize;
+            let y_end = bottom.clamp(0.0, height) as usize;
+
+            for x in x_start..x_end {
+                if distance >= self.zbuffer[x] {
+                    continue;
+                }
+                let tx = ((x as f32 - left) / sprite_width * image.width as f32) as u32;
+                for y in y_start..y_end {
+                    let ty = ((y as f32 - top) / sprite_height * image.height as f32) as u32;
+                    let color = image.get_pixel_color(tx, ty);
+                    // Transparencia por clave de alfa
+                    if color.a > 127 {
+                        framebuffer.set(x, y, color);
+                    }
+                }
+            }
+        }
+    }
+
+    fn sample_surface(
+        &self,
+        textures: &TextureSet,
+        texture: Option<char>,
+        world_x: f32,
+        world_y: f32,
+        fallback: Color,
+    ) -> Color {
+        match texture.and_then(|ch| textures.get(ch)) {
+            Some(image) => {
+                let tx = (world_x.rem_euclid(1.0) * image.width as f32) as u32;
+                let ty = (world_y.rem_euclid(1.0) * image.height as f32) as u32;
+                image.get_pixel_color(tx, ty)
+            }
+            None => fallback,
+        }
+    }
+}
+
+fn shade_color(color: Color, factor: f32) -> Color {
+    Color::new(
+        (color.r as f32 * factor) as u8,
+        (color.g as f32 * factor) as u8,
+        (color.b as f32 * factor) as u8,
+        color.a,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::Layer;
+    use crate::textures::TextureImage;
+
+    const WALL: Color = Color {
+        r: 200,
+        g: 100,
+        b: 50,
+        a: 255,
+    };
+    const BACKGROUND: Color = Color::BLACK;
+
+    // Pasillo de una celda de ancho; la cámara mira al este hacia la pared del fondo
+    fn render_corridor() -> (Vec<Option<RayHit>>, Vec<Color>) {
+        let grid = ["#####", "#   #", "#####"]
+            .iter()
+            .map(|row| row.chars().collect())
+            .collect();
+        let maze = Maze::new(grid, Layer::default(), Layer::default());
+        let mut textures = TextureSet::new();
+        textures.insert('#', TextureImage::generate(4, |_, _| WALL));
+        let camera = Camera {
+            pos: Vector2::new(1.5, 1.5),
+            angle: 0.0,
+            fov: std::f32::consts::FRAC_PI_3,
+        };
+        let mut framebuffer = Framebuffer::new(32, 24, BACKGROUND);
+        let hits = Renderer::new(20.0).render(
+            &maze,
+            &Doors::default(),
+            &camera,
+            &textures,
+            &mut framebuffer,
+        );
+        (hits, framebuffer.to_image().get_image_data().to_vec())
+    }
+
+    fn column(pixels: &[Color], x: usize) -> Vec<Color> {
+        (0..24).map(|y| pixels[y * 32 + x]).collect()
+    }
+
+    #[test]
+    fn center_column_has_sky_wall_and_floor() {
+        let (hits, pixels) = render_corridor();
+        let hit = hits[16].expect("la columna central ve la pared del fondo");
+        assert_eq!((hit.cell, hit.side), ((4, 1), Side::West));
+        assert_eq!(hit.distance, 2.5);
+
+        // Alto de pared: 24 * 1.5 / 2.5 = 14.4 pixeles centrados en el horizonte,
+        // o sea de la fila 4 a la 18; la cara este/oeste no se sombrea
+        let mut expected = vec![Color::DARKBLUE; 4];
+        expected.extend([WALL; 15]);
+        expected.extend([Color::DARKGRAY; 5]);
+        assert_eq!(column(&pixels, 16), expected);
+    }
+
+    #[test]
+    fn side_walls_are_shaded_and_fill_the_column() {
+        let (hits, pixels) = render_corridor();
+        // El borde izquierdo mira 30 grados al norte y choca con la pared de arriba
+        let hit = hits[0].expect("la columna del borde ve la pared lateral");
+        assert_eq!((hit.cell.1, hit.side), (0, Side::South));
+        assert!((hit.distance - 1.0).abs() < 1e-5);
+        let shaded = Color::new(160, 80, 40, 255);
+        assert_eq!(column(&pixels, 0), vec![shaded; 24]);
+    }
+
+    #[test]
+    fn floor_and_ceiling_are_mirrored_around_the_horizon() {
+        let (_, pixels) = render_corridor();
+        for x in [8, 16, 24] {
+            let column = column(&pixels, x);
+            assert_eq!(column[0], Color::DARKBLUE, "columna {x}");
+            assert_eq!(column[23], Color::DARKGRAY, "columna {x}");
+            assert!(!column.contains(&BACKGROUND), "columna {x}");
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const VERSION: u32 = 1;
+const PATH_VARIABLE: &str = "RAYCASTER_SAVE"; // Cambia la ubicación del archivo
+const FILE_NAME: &str = "progreso.txt";
+
+// Mejor resultado en un nivel
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Record {
+    pub best_time: Option<f32>,
+    pub best_score: u32,
+    pub completed: bool,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    UnsupportedVersion(String),
+    InvalidLine { line: usize, text: String },
+    Missing(&'static str),
+    ReadOnly, // El archivo no se pudo leer ni apartar: no se pisa
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "{e}"),
+            SaveError::UnsupportedVersion(header) => {
+                write!(f, "versión de guardado no soportada '{header}'")
+            }
+            SaveError::InvalidLine { line, text } => {
+                write!(f, "línea {line}: dato inválido '{text}'")
+            }
+            SaveError::Missing(key) => write!(f, "falta el dato '{key}'"),
+            SaveError::ReadOnly => write!(f, "el archivo anterior no se pudo leer"),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
+// Progreso entre partidas, indexado por el archivo de cada nivel
+pub struct SaveData {
+    path: PathBuf,
+    records: HashMap<String, Record>,
+    pub skipped: usize, // Líneas dañadas que se ignoraron al cargar
+    read_only: bool,
+}
+
+impl SaveData {
+    pub fn new(path: PathBuf) -> Self {
+        SaveData {
+            path,
+            records: HashMap::new(),
+            skipped: 0,
+            read_only: false,
+        }
+    }
+
+    // $RAYCASTER_SAVE, o el directorio de datos del usuario
+    pub fn default_path() -> PathBuf {
+        if let Some(path) = env::var_os(PATH_VARIABLE) {
+            return PathBuf::from(path);
+        }
+        let data_dir = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+            .unwrap_or_default();
+        data_dir.join("raycaster").join(FILE_NAME)
+    }
+
+    // Sin archivo se empieza de cero
+    pub fn load(path: PathBuf) -> Result<SaveData, SaveError> {
+        match fs::read_to_string(&path) {
+            Ok(text) => SaveData::parse(path, &text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(SaveData::new(path)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // Para cuando `load` falla: aparta el archivo (progreso.txt.bak) y empieza de
+    // cero. Si ni eso se puede, esta sesión no escribe récords para no pisar los
+    // viejos. Devuelve también el aviso para el jugador.
+    pub fn recover(path: PathBuf) -> (SaveData, String) {
+        let mut data = SaveData::new(path);
+        match set_aside(&data.path) {
+            Ok(backup) => (data, format!("se apartó en {}", backup.display())),
+            Err(_) => {
+                data.read_only = true;
+                (data, "no se guardarán récords".to_string())
+            }
+        }
+    }
+
+    // Formato: la primera línea es "version N"; después un nivel por línea:
+    //   nivel <mejor tiempo o -> <mejor puntaje> <completado 0|1> <archivo>
+    // Las líneas que no se entienden, la de la versión incluida, se saltan para no
+    // perder el resto. Solo una versión distinta hace fallar la carga.
+    pub fn parse(path: PathBuf, text: &str) -> Result<SaveData, SaveError> {
+        let mut lines = text.lines().peekable();
+        let header = lines.peek().map_or("", |line| line.trim());
+        let version = header
+            .strip_prefix("version ")
+            .and_then(|v| v.trim().parse::<u32>().ok());
+        match version {
+            Some(VERSION) => {
+                lines.next();
+            }
+            Some(_) => return Err(SaveError::UnsupportedVersion(header.to_string())),
+            None => {}
+        }
+
+        let mut data = SaveData::new(path);
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            match parse_record(line) {
+                Some((id, record)) => {
+                    data.records.insert(id, record);
+                }
+                None => data.skipped += 1,
+            }
+        }
+        Ok(data)
+    }
+
+    pub fn save(&self) -> Result<(), SaveError> {
+        if self.read_only {
+            return Err(SaveError::ReadOnly);
+        }
+        let mut text = format!("version {VERSION}\n");
+        let mut ids: Vec<&String> = self.records.keys().collect();
+        ids.sort();
+        for id in ids {
+            let record = self.records[id];
+            let time = match record.best_time {
+                Some(time) => format!("{time:.2}"),
+                None => "-".to_string(),
+            };
+            let completed = u8::from(record.completed);
+            text += &format!("nivel {time} {} {completed} {id}\n", record.best_score);
+        }
+        write_file(&self.path, &text)?;
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Record> {
+        self.records.get(id)
+    }
+
+    // Guarda el resultado si mejora el anterior; devuelve true si hubo récord de tiempo
+    pub fn record(&mut self, id: &str, time: f32, score: u32) -> bool {
+        let record = self.records.entry(id.to_string()).or_default();
+        let new_best = record.best_time.is_none_or(|best| time < best);
+        if new_best {
+            record.best_time = Some(time);
+        }
+        record.best_score = record.best_score.max(score);
+        record.completed = true;
+        new_best
+    }
+}
+
+// Escribe a un archivo temporal y lo renombra, así un corte no deja el guardado a medias
+pub fn write_file(path: &Path, text: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, text)?;
+    fs::rename(&temp, path)?;
+    Ok(())
+}
+
+// Renombra un archivo que no se pudo cargar a `archivo.bak`, para que el próximo
+// guardado no lo pise
+pub fn set_aside(path: &Path) -> io::Result<PathBuf> {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    let backup = PathBuf::from(backup);
+    fs::rename(path, &backup)?;
+    Ok(backup)
+}
+
+fn parse_record(line: &str) -> Option<(String, Record)> {
+    let mut parts = line.trim().splitn(5, ' ');
+    if parts.next()? != "nivel" {
+        return None;
+    }
+    let best_time = match parts.next()? {
+        "-" => None,
+        time => {
+            let time: f32 = time.parse().ok()?;
+            if !time.is_finite() || time < 0.0 {
+                return None;
+            }
+            Some(time)
+        }
+    };
+    let best_score = parts.next()?.parse().ok()?;
+    let completed = match parts.next()? {
+        "0" => false,
+        "1" => true,
+        _ => return None,
+    };
+    let id = parts.next()?.trim();
+    if id.is_empty() {
+        return None;
+    }
+    Some((
+        id.to_string(),
+        Record {
+            best_time,
+            best_score,
+            completed,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn damaged_header_is_skipped_like_any_line() {
+        let text = "versi\u{fffd}n 1\nnivel 12.50 300 1 maze.txt\n";
+        let data = SaveData::parse(PathBuf::new(), text).unwrap();
+        assert_eq!(data.skipped, 1);
+        assert_eq!(data.get("maze.txt").unwrap().best_score, 300);
+    }
+
+    #[test]
+    fn other_versions_are_not_read() {
+        let text = "version 2\nnivel 12.50 300 1 maze.txt\n";
+        assert!(matches!(
+            SaveData::parse(PathBuf::new(), text),
+            Err(SaveError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn recover_keeps_the_unreadable_file() {
+        let dir = env::temp_dir().join(format!("raycaster-save-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(FILE_NAME);
+        fs::write(&path, "version 99\n").unwrap();
+
+        let (mut data, _) = SaveData::recover(path.clone());
+        data.record("maze.txt", 10.0, 100);
+        data.save().unwrap();
+        let backup = fs::read_to_string(dir.join("progreso.txt.bak")).unwrap();
+        assert_eq!(backup, "version 99\n");
+
+        // Si no se puede apartar, los récords nuevos no se escriben
+        let (data, _) = SaveData::recover(dir.join("no-existe.txt"));
+        assert!(matches!(data.save(), Err(SaveError::ReadOnly)));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
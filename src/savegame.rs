@@ -0,0 +1,402 @@
+use raylib::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::door::{DoorState, Doors};
+use crate::item::Item;
+use crate::level::{LevelSource, RANDOM_LEVEL_SIZE};
+use crate::maze::generate::Algorithm;
+use crate::maze::Maze;
+use crate::pathfinding::Cell;
+use crate::player::Player;
+use crate::save::{self, SaveData, SaveError};
+use crate::stats::Stats;
+use crate::world::{Entity, Transform, World};
+
+const VERSION: u32 = 1;
+const FILE_NAME: &str = "partida.txt";
+
+// Foto de una partida a medio nivel: el nivel se vuelve a cargar desde `source`
+// y después se le aplica el resto
+pub struct SavedGame {
+    pub source: LevelSource,
+    pub pos: Vector2,
+    pub angle: f32,
+    pub health: f32,
+    pub armor: f32,
+    pub ammo: u32,
+    pub inventory: Vec<Item>,
+    pub doors: Vec<(Cell, f32, DoorState)>, // Celda, apertura y estado
+    pub walls: Vec<(Cell, char)>,           // Paredes que cambiaron los disparadores
+    pub pickups: Vec<(Vector2, Item)>,      // Objetos que siguen en el piso
+    // Posición, ángulo y salud de cada enemigo, en el orden del nivel
+    pub enemies: Vec<(Vector2, f32, f32)>,
+    pub time: f32,
+    pub score: u32,
+    pub collected: usize,
+    pub visited: Vec<bool>,
+}
+
+impl SavedGame {
+    pub fn capture(
+        source: &LevelSource,
+        world: &World,
+        doors: &Doors,
+        walls: &[(Cell, char)],
+        stats: &Stats,
+    ) -> Self {
+        let player = &world.player;
+        let body = world.player_body();
+        SavedGame {
+            source: source.clone(),
+            pos: body.pos,
+            angle: body.angle,
+            health: player.health,
+            armor: player.armor,
+            ammo: player.weapon.ammo,
+            inventory: player.inventory.items().to_vec(),
+            doors: doors
+                .iter()
+                .map(|door| (door.cell, door.open, door.state))
+                .collect(),
+            walls: walls.to_vec(),
+            pickups: world.pickups().collect(),
+            enemies: world
+                .enemies()
+                .map(|(body, enemy)| (body.pos, body.angle, enemy.health))
+                .collect(),
+            time: stats.time,
+            score: stats.score,
+            collected: stats.collected,
+            visited: stats.visited.clone(),
+        }
+    }
+
+    // Sobre un nivel recién cargado desde `source`
+    pub fn apply(&self, world: &mut World, maze: &mut Maze, doors: &mut Doors, stats: &mut Stats) {
+        world.player = Player::default();
+        *world.player_body_mut() = Transform::new(self.pos, self.angle);
+        world.settle_triggers();
+        let player = &mut world.player;
+        player.health = self.health;
+        player.armor = self.armor;
+        player.weapon.ammo = self.ammo;
+        for &item in &self.inventory {
+            player.inventory.add(item);
+        }
+        for &(cell, open, state) in &self.doors {
+            doors.restore(cell, open, state);
+        }
+        for &(cell, ch) in &self.walls {
+            maze.set_wall(cell, ch);
+        }
+        world.retain(|entity| entity.pickup.is_none());
+        for &(pos, item) in &self.pickups {
+            world.spawn(Entity::pickup(pos, item));
+        }
+        let enemies = world.entities_mut().iter_mut().filter(|e| e.ai.is_some());
+        for (entity, &(pos, angle, health)) in enemies.zip(&self.enemies) {
+            let Some(enemy) = &mut entity.ai else { continue };
+            enemy.restore(health);
+            entity.transform = Transform::new(pos, angle);
+            entity.sprite = Some(enemy.kind.sprite(enemy.is_dead()));
+        }
+        stats.time = self.time;
+        stats.score = self.score;
+        stats.collected = self.collected;
+        // Si el archivo del nivel cambió de tamaño, el recorrido no sirve
+        if self.visited.len() == stats.visited.len() {
+            stats.visited = self.visited.clone();
+        }
+    }
+
+    // Junto al archivo de récords
+    pub fn default_path() -> PathBuf {
+        SaveData::default_path().with_file_name(FILE_NAME)
+    }
+
+    pub fn load(path: &Path) -> Result<SavedGame, SaveError> {
+        SavedGame::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SaveError> {
+        save::write_file(path, &self.to_text())?;
+        Ok(())
+    }
+
+    // Formato: "version N" y después una línea por dato
+    //   level file maze.txt | level random prim 1234 8
+    //   player x y ángulo
+    //   health salud armadura
+    //   ammo balas
+    //   stats tiempo puntos recogidos
+    //   inventory key_red ...
+    //   visited 0110...     (una cifra por celda, fila por fila)
+    //   door x y apertura estado
+    //   wall x y letra
+    //   pickup x y objeto
+    //   enemy x y ángulo salud  (uno por enemigo, en el orden del nivel)
+    pub fn to_text(&self) -> String {
+        let mut text = format!("version {VERSION}\n");
+        text += &match &self.source {
+            LevelSource::File(path) => format!("level file {path}\n"),
+            LevelSource::Random {
+                algorithm,
+                seed,
+                size,
+            } => format!("level random {} {seed} {size}\n", algorithm.name()),
+        };
+        text += &format!("player {} {} {}\n", self.pos.x, self.pos.y, self.angle);
+        text += &format!("health {} {}\n", self.health, self.armor);
+        text += &format!("ammo {}\n", self.ammo);
+        text += &format!("stats {} {} {}\n", self.time, self.score, self.collected);
+        let inventory: Vec<String> = self.inventory.iter().map(|item| item.name()).collect();
+        text += &format!("inventory {}\n", inventory.join(" "));
+        let visited: String = self
+            .visited
+            .iter()
+            .map(|&v| if v { '1' } else { '0' })
+            .collect();
+        text += &format!("visited {visited}\n");
+        for ((x, y), open, state) in &self.doors {
+            text += &format!("door {x} {y} {open} {}\n", state.name());
+        }
+        for ((x, y), ch) in &self.walls {
+            text += &format!("wall {x} {y} {ch}\n");
+        }
+        for (pos, item) in &self.pickups {
+            text += &format!("pickup {} {} {}\n", pos.x, pos.y, item.name());
+        }
+        for (pos, angle, health) in &self.enemies {
+            text += &format!("enemy {} {} {angle} {health}\n", pos.x, pos.y);
+        }
+        text
+    }
+
+    // A diferencia de los récords, una línea dañada invalida toda la partida
+    pub fn parse(text: &str) -> Result<SavedGame, SaveError> {
+        let mut lines = text.lines().enumerate();
+        let header = lines.next().map_or("", |(_, line)| line.trim());
+        if header != format!("version {VERSION}") {
+            return Err(SaveError::UnsupportedVersion(header.to_string()));
+        }
+
+        let mut source = None;
+        let mut player = None;
+        let mut health = None;
+        let mut ammo = None;
+        let mut stats = (0.0, 0, 0);
+        let mut inventory = Vec::new();
+        let mut visited = Vec::new();
+        let mut doors = Vec::new();
+        let mut walls = Vec::new();
+        let mut pickups = Vec::new();
+        let mut enemies = Vec::new();
+        for (index, line) in lines {
+            let invalid = || SaveError::InvalidLine {
+                line: index + 1,
+                text: line.to_string(),
+            };
+            let (key, rest) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+            let values: Vec<&str> = rest.split_whitespace().collect();
+            let number_at = |i: usize| -> Result<f32, SaveError> {
+                values
+                    .get(i)
+                    .and_then(|v| v.parse::<f32>().ok())
+                    .filter(|v| v.is_finite())
+                    .ok_or_else(invalid)
+            };
+            let integer_at = |i: usize| -> Result<u64, SaveError> {
+                values
+                    .get(i)
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(invalid)
+            };
+            let item_at = |i: usize| -> Result<Item, SaveError> {
+                values
+                    .get(i)
+                    .and_then(|v| Item::from_name(v))
+                    .ok_or_else(invalid)
+            };
+
+            match key {
+                "" => {}
+                "level" => match rest.split_once(' ') {
+                    Some(("file", path)) if !path.trim().is_empty() => {
+                        source = Some(LevelSource::File(path.trim().to_string()))
+                    }
+                    Some(("random", _)) => {
+                        let algorithm = values
+                            .get(1)
+                            .and_then(|name| Algorithm::from_name(name))
+                            .ok_or_else(invalid)?;
+                        // Un tamaño enorme haría generar un mapa gigante
+                        let size = integer_at(3)?;
+                        if !(2..=RANDOM_LEVEL_SIZE as u64).contains(&size) {
+                            return Err(invalid());
+                        }
+                        source = Some(LevelSource::Random {
+                            algorithm,
+                            seed: integer_at(2)?,
+                            size: size as usize,
+                        });
+                    }
+                    _ => return Err(invalid()),
+                },
+                "player" => player = Some((number_at(0)?, number_at(1)?, number_at(2)?)),
+                // Una partida guardada siempre tiene al jugador con vida
+                "health" => match (number_at(0)?, number_at(1)?) {
+                    (h, a) if h > 0.0 && a >= 0.0 => health = Some((h, a)),
+                    _ => return Err(invalid()),
+                },
+                "ammo" => ammo = Some(integer_at(0)? as u32),
+                "stats" => {
+                    stats = (
+                        number_at(0)?,
+                        integer_at(1)? as u32,
+                        integer_at(2)? as usize,
+                    )
+                }
+                "inventory" => {
+                    inventory = (0..values.len()).map(item_at).collect::<Result<_, _>>()?
+                }
+                "visited" => {
+                    visited = rest
+                        .trim()
+                        .chars()
+                        .map(|c| match c {
+                            '0' => Ok(false),
+                            '1' => Ok(true),
+                            _ => Err(invalid()),
+                        })
+                        .collect::<Result<_, _>>()?
+                }
+                "door" => {
+                    let cell = (integer_at(0)? as usize, integer_at(1)? as usize);
+                    let state = values
+                        .get(3)
+                        .and_then(|name| DoorState::from_name(name))
+                        .ok_or_else(invalid)?;
+                    doors.push((cell, number_at(2)?, state));
+                }
+                "wall" => {
+                    let cell = (integer_at(0)? as usize, integer_at(1)? as usize);
+                    let mut chars = values.get(2).ok_or_else(invalid)?.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(ch), None) => walls.push((cell, ch)),
+                        _ => return Err(invalid()),
+                    }
+                }
+                "pickup" => {
+                    let pos = Vector2::new(number_at(0)?, number_at(1)?);
+                    pickups.push((pos, item_at(2)?));
+                }
+                "enemy" => {
+                    let pos = Vector2::new(number_at(0)?, number_at(1)?);
+                    enemies.push((pos, number_at(2)?, number_at(3)?));
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        // Sin nivel o sin jugador no hay nada que continuar
+        let (x, y, angle) = player.ok_or(SaveError::Missing("player"))?;
+        let (health, armor) = health.ok_or(SaveError::Missing("health"))?;
+        let ammo = ammo.ok_or(SaveError::Missing("ammo"))?;
+        let (time, score, collected) = stats;
+        Ok(SavedGame {
+            source: source.ok_or(SaveError::Missing("level"))?,
+            pos: Vector2::new(x, y),
+            angle,
+            health,
+            armor,
+            ammo,
+            inventory,
+            doors,
+            walls,
+            pickups,
+            enemies,
+            time,
+            score,
+            collected,
+            visited,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::Level;
+    use crate::trigger::TriggerAction;
+
+    const LEVEL: &str = "\
+@trigger 2 1 enter message hola
+@trigger 2 1 exit end
+######
+#P   #
+#   G#
+######
+";
+
+    #[test]
+    fn loading_inside_a_zone_does_not_refire_it() {
+        let mut level = Level::parse(LEVEL).unwrap();
+        let mut world = World::from_level(&level);
+        let mut doors = Doors::new(&level.maze, &level.locks);
+        let mut stats = Stats::new(&level);
+        let text = "version 1\nlevel file nivel.txt\nplayer 2.5 1.5 0\nhealth 100 0\nammo 12\n";
+        let saved = SavedGame::parse(text).unwrap();
+        saved.apply(&mut world, &mut level.maze, &mut doors, &mut stats);
+
+        assert!(world.step_triggers().is_empty());
+        // La salida pendiente sigue esperando a que se vaya
+        world.player_body_mut().pos = Vector2::new(3.5, 1.5);
+        assert_eq!(world.step_triggers(), vec![TriggerAction::EndLevel]);
+    }
+
+    #[test]
+    fn health_ammo_and_enemy_health_are_required() {
+        let base = "version 1\nlevel file nivel.txt\nplayer 2.5 1.5 0\n";
+        assert!(matches!(
+            SavedGame::parse(&format!("{base}ammo 12\n")),
+            Err(SaveError::Missing("health"))
+        ));
+        assert!(matches!(
+            SavedGame::parse(&format!("{base}health 100 0\n")),
+            Err(SaveError::Missing("ammo"))
+        ));
+        let full = format!("{base}health 100 0\nammo 12\n");
+        assert!(matches!(
+            SavedGame::parse(&format!("{full}enemy 3.5 1.5 0\n")),
+            Err(SaveError::InvalidLine { line: 6, .. })
+        ));
+        let saved = SavedGame::parse(&format!("{full}enemy 3.5 1.5 0 20\n")).unwrap();
+        assert_eq!(saved.enemies, vec![(Vector2::new(3.5, 1.5), 0.0, 20.0)]);
+        let again = SavedGame::parse(&saved.to_text()).unwrap();
+        assert_eq!(again.enemies, saved.enemies);
+    }
+
+    #[test]
+    fn random_level_size_must_be_in_range() {
+        let with_size = |size: &str| {
+            SavedGame::parse(&format!(
+                "version 1\nlevel random prim 1234 {size}\nplayer 1.5 1.5 0\nhealth 100 0\nammo 12\n"
+            ))
+        };
+        let saved = with_size("8").unwrap();
+        assert_eq!(
+            saved.source,
+            LevelSource::Random {
+                algorithm: Algorithm::Prim,
+                seed: 1234,
+                size: 8,
+            }
+        );
+        for size in ["0", "1", "9", "18446744073709551615"] {
+            assert!(
+                matches!(with_size(size), Err(SaveError::InvalidLine { line: 2, .. })),
+                "tamaño {size}"
+            );
+        }
+    }
+}
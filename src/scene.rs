@@ -0,0 +1,130 @@
+use raylib::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use crate::animation::Animation;
+use crate::framebuffer::Framebuffer;
+use crate::input::InputState;
+use crate::save::SaveData;
+use crate::settings::Settings;
+
+pub mod game_over;
+pub mod options;
+pub mod paused;
+pub mod playing;
+pub mod success;
+pub mod welcome;
+
+// Una pantalla del juego. `update` lee la entrada y avanza la simulación;
+// `draw` solo dibuja, con lo que dejó el último `update`.
+pub trait Scene {
+    fn update(&mut self, ctx: &mut Context, dt: f32) -> Transition;
+    fn draw(&self, ctx: &Context, d: &mut RaylibDrawHandle);
+
+    // Las superposiciones (pausa) dejan ver la escena de abajo
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+// Qué hacer con la pila de escenas después de un `update`
+pub enum Transition {
+    None,
+    Push(Box<dyn Scene>),
+    Pop,
+    Replace(Box<dyn Scene>), // Cambia la escena de arriba por otra
+    Reset(Box<dyn Scene>),   // Vacía la pila y deja solo esta
+    Quit,
+}
+
+// Recursos compartidos por todas las escenas
+pub struct Context<'a> {
+    pub audio: &'a RaylibAudio,
+    pub step_sound: Sound<'a>,
+    pub pickup_sound: Sound<'a>,
+    pub fire_sound: Sound<'a>,
+    pub music: Option<Music<'a>>, // Música del nivel en juego
+    pub level_sounds: HashMap<String, Sound<'a>>, // Sonidos de los disparadores, por archivo
+    pub input: InputState,        // Entrada de este cuadro
+    pub time: f64,                // Segundos desde que se abrió la ventana
+    pub fps: u32,
+    pub settings: Settings,
+    pub settings_locked: bool, // settings.cfg no se pudo leer ni apartar: no se pisa
+    pub save_data: SaveData,
+    pub saved_game_path: PathBuf, // Partida guardada a medio nivel
+    pub levels: Vec<String>,
+    pub level_entries: Vec<Result<String, String>>, // Nombre de cada nivel o su error
+    pub framebuffer: Framebuffer,
+    pub frame_ready: bool, // Hay un cuadro nuevo en el framebuffer para subir
+    pub star_animation: Animation<Texture2D>,
+    pub welcome_texture: Texture2D,
+    pub success_texture: Texture2D,
+    pub message: String, // Mensaje temporal en pantalla (capturas, etc.)
+    pub message_timer: f32,
+}
+
+impl Context<'_> {
+    pub fn show_message(&mut self, text: impl Into<String>, seconds: f32) {
+        self.message = text.into();
+        self.message_timer = seconds;
+    }
+
+    // Volúmenes de las opciones sobre la música y los efectos
+    pub fn apply_volumes(&self) {
+        if let Some(music) = &self.music {
+            music.set_volume(self.settings.music_volume);
+        }
+        self.step_sound.set_volume(self.settings.sfx_volume);
+        self.pickup_sound.set_volume(self.settings.sfx_volume);
+        self.fire_sound.set_volume(self.settings.sfx_volume);
+        for sound in self.level_sounds.values() {
+            sound.set_volume(self.settings.sfx_volume);
+        }
+    }
+}
+
+// Pila de escenas: solo la de arriba se actualiza
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+}
+
+impl SceneStack {
+    pub fn new(first: Box<dyn Scene>) -> Self {
+        SceneStack {
+            scenes: vec![first],
+        }
+    }
+
+    // Devuelve false cuando hay que cerrar el juego
+    pub fn update(&mut self, ctx: &mut Context, dt: f32) -> bool {
+        let Some(top) = self.scenes.last_mut() else { return false };
+        match top.update(ctx, dt) {
+            Transition::None => {}
+            Transition::Push(scene) => self.scenes.push(scene),
+            Transition::Pop => {
+                self.scenes.pop();
+            }
+            Transition::Replace(scene) => {
+                self.scenes.pop();
+                self.scenes.push(scene);
+            }
+            Transition::Reset(scene) => {
+                self.scenes.clear();
+                self.scenes.push(scene);
+            }
+            Transition::Quit => return false,
+        }
+        !self.scenes.is_empty()
+    }
+
+    // Desde la última escena opaca hacia arriba
+    pub fn draw(&self, ctx: &Context, d: &mut RaylibDrawHandle) {
+        let start = self
+            .scenes
+            .iter()
+            .rposition(|scene| !scene.is_overlay())
+            .unwrap_or(0);
+        for scene in &self.scenes[start..] {
+            scene.draw(ctx, d);
+        }
+    }
+}
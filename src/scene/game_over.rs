@@ -0,0 +1,66 @@
+use raylib::prelude::*;
+use super::playing::Playing;
+use super::welcome::Welcome;
+use super::{Context, Scene, Transition};
+use crate::input::Action;
+use crate::level::LevelSource;
+use crate::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+const GAME_OVER_MENU: [&str; 2] = ["Reintentar", "Volver al menú"];
+
+// Fin de la partida: se puede volver a intentar el mismo nivel
+pub struct GameOver {
+    source: LevelSource,
+    reason: String, // Qué terminó con el jugador
+    selected: usize,
+}
+
+impl GameOver {
+    pub fn new(source: LevelSource, reason: String) -> Self {
+        GameOver {
+            source,
+            reason,
+            selected: 0,
+        }
+    }
+}
+
+impl Scene for GameOver {
+    fn update(&mut self, ctx: &mut Context, _dt: f32) -> Transition {
+        let input = &ctx.input;
+        if input.is_pressed(Action::MenuUp) || input.is_pressed(Action::MenuDown) {
+            self.selected = 1 - self.selected;
+        }
+
+        let confirm = input.is_pressed(Action::Confirm);
+        if confirm && self.selected == 0 {
+            match Playing::start(ctx, self.source.clone(), None) {
+                Ok(playing) => Transition::Reset(Box::new(playing)),
+                Err(e) => {
+                    ctx.show_message(e, 3.0);
+                    Transition::Reset(Box::new(Welcome::default()))
+                }
+            }
+        } else if confirm || input.is_pressed(Action::Pause) {
+            Transition::Reset(Box::new(Welcome::default()))
+        } else {
+            Transition::None
+        }
+    }
+
+    fn draw(&self, _ctx: &Context, d: &mut RaylibDrawHandle) {
+        d.draw_rectangle(0, 0, SCREEN_WIDTH, SCREEN_HEIGHT, Color::new(60, 0, 0, 255));
+        d.draw_text("FIN DEL JUEGO", SCREEN_WIDTH / 2 - 160, 120, 40, Color::RED);
+        let x = SCREEN_WIDTH / 2 - d.measure_text(&self.reason, 25) / 2;
+        d.draw_text(&self.reason, x, 190, 25, Color::WHITE);
+        for (i, label) in GAME_OVER_MENU.iter().enumerate() {
+            let color = if i == self.selected {
+                Color::YELLOW
+            } else {
+                Color::WHITE
+            };
+            let y = 280 + i as i32 * 40;
+            d.draw_text(label, SCREEN_WIDTH / 2 - 100, y, 25, color);
+        }
+    }
+}
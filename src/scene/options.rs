@@ -0,0 +1,83 @@
+use raylib::prelude::*;
+use super::{Context, Scene, Transition};
+use crate::framebuffer::Framebuffer;
+use crate::input::Action;
+use crate::settings::Setting;
+use crate::{SCREEN_WIDTH, SETTINGS_PATH};
+
+// Pantalla de opciones: una fila por opción más "Volver"
+#[derive(Default)]
+pub struct Options {
+    selected: usize,
+}
+
+impl Scene for Options {
+    fn update(&mut self, ctx: &mut Context, _dt: f32) -> Transition {
+        let rows = Setting::ALL.len() + 1;
+        if ctx.input.is_pressed(Action::MenuUp) {
+            self.selected = (self.selected + rows - 1) % rows;
+        }
+        if ctx.input.is_pressed(Action::MenuDown) {
+            self.selected = (self.selected + 1) % rows;
+        }
+
+        let confirm = ctx.input.is_pressed(Action::Confirm);
+        if let Some(&setting) = Setting::ALL.get(self.selected) {
+            let mut direction = ctx.input.is_pressed(Action::MenuRight) as i32
+                - ctx.input.is_pressed(Action::MenuLeft) as i32;
+            if confirm && setting == Setting::Fullscreen {
+                direction = 1;
+            }
+            if direction != 0 {
+                // Los cambios se ven enseguida; la pantalla completa la aplica el bucle principal
+                ctx.settings.adjust(setting, direction);
+                ctx.apply_volumes();
+                let (width, height) = ctx.settings.resolution_size();
+                if (width, height) != (ctx.framebuffer.width(), ctx.framebuffer.height()) {
+                    ctx.framebuffer = Framebuffer::new(width, height, Color::DARKBLUE);
+                }
+            }
+        }
+
+        // Al salir se guardan en el archivo
+        if ctx.input.is_pressed(Action::Pause) || (confirm && self.selected == rows - 1) {
+            if ctx.settings_locked {
+                let note = format!("{SETTINGS_PATH} no se pudo leer: no se guardan las opciones");
+                ctx.show_message(note, 3.0);
+            } else if let Err(e) = ctx.settings.save(SETTINGS_PATH) {
+                ctx.show_message(format!("No se pudieron guardar las opciones: {e}"), 3.0);
+            }
+            return Transition::Pop;
+        }
+        Transition::None
+    }
+
+    fn draw(&self, ctx: &Context, d: &mut RaylibDrawHandle) {
+        d.draw_text("OPCIONES", SCREEN_WIDTH / 2 - 90, 80, 40, Color::WHITE);
+        for (i, setting) in Setting::ALL.iter().enumerate() {
+            let color = if i == self.selected {
+                Color::YELLOW
+            } else {
+                Color::WHITE
+            };
+            let y = 170 + i as i32 * 40;
+            d.draw_text(setting.label(), 120, y, 20, color);
+            let value = format!("< {} >", ctx.settings.value_text(*setting));
+            d.draw_text(&value, 480, y, 20, color);
+        }
+        let back_color = if self.selected == Setting::ALL.len() {
+            Color::YELLOW
+        } else {
+            Color::WHITE
+        };
+        let back_y = 170 + Setting::ALL.len() as i32 * 40 + 20;
+        d.draw_text("Volver", 120, back_y, 20, back_color);
+        d.draw_text(
+            "IZQUIERDA/DERECHA para cambiar, ESC para volver",
+            SCREEN_WIDTH / 2 - 250,
+            520,
+            20,
+            Color::GRAY,
+        );
+    }
+}
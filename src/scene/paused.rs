@@ -0,0 +1,81 @@
+use raylib::prelude::*;
+use super::options::Options;
+use super::playing::Playing;
+use super::welcome::Welcome;
+use super::{Context, Scene, Transition};
+use crate::input::Action;
+use crate::level::LevelSource;
+use crate::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+const PAUSE_MENU: [&str; 4] = ["Continuar", "Reiniciar nivel", "Opciones", "Salir al menú"];
+
+// Menú de pausa sobre el último cuadro del juego
+pub struct Paused {
+    source: LevelSource, // Para reiniciar el nivel
+    selected: usize,
+}
+
+impl Paused {
+    pub fn new(source: LevelSource) -> Self {
+        Paused {
+            source,
+            selected: 0,
+        }
+    }
+}
+
+impl Scene for Paused {
+    fn update(&mut self, ctx: &mut Context, _dt: f32) -> Transition {
+        let input = &ctx.input;
+        if input.is_pressed(Action::MenuUp) {
+            self.selected = (self.selected + PAUSE_MENU.len() - 1) % PAUSE_MENU.len();
+        }
+        if input.is_pressed(Action::MenuDown) {
+            self.selected = (self.selected + 1) % PAUSE_MENU.len();
+        }
+
+        let confirm = input.is_pressed(Action::Confirm);
+        if input.is_pressed(Action::Pause) || (confirm && self.selected == 0) {
+            if let Some(music) = &ctx.music {
+                music.resume_stream();
+            }
+            Transition::Pop
+        } else if confirm && self.selected == 1 {
+            // El mismo nivel desde el principio
+            match Playing::start(ctx, self.source.clone(), None) {
+                Ok(playing) => Transition::Reset(Box::new(playing)),
+                Err(e) => {
+                    ctx.music = None;
+                    ctx.show_message(e, 3.0);
+                    Transition::Reset(Box::new(Welcome::default()))
+                }
+            }
+        } else if confirm && self.selected == 2 {
+            Transition::Push(Box::new(Options::default()))
+        } else if confirm {
+            ctx.music = None;
+            Transition::Reset(Box::new(Welcome::default()))
+        } else {
+            Transition::None
+        }
+    }
+
+    fn draw(&self, _ctx: &Context, d: &mut RaylibDrawHandle) {
+        // El juego de abajo queda oscurecido
+        d.draw_rectangle(0, 0, SCREEN_WIDTH, SCREEN_HEIGHT, Color::new(0, 0, 0, 160));
+        d.draw_text("PAUSA", SCREEN_WIDTH / 2 - 60, 150, 40, Color::WHITE);
+        for (i, label) in PAUSE_MENU.iter().enumerate() {
+            let color = if i == self.selected {
+                Color::YELLOW
+            } else {
+                Color::WHITE
+            };
+            let y = 250 + i as i32 * 40;
+            d.draw_text(label, SCREEN_WIDTH / 2 - 100, y, 25, color);
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,562 @@
+use raylib::prelude::*;
+use std::collections::HashSet;
+use super::game_over::GameOver;
+use super::paused::Paused;
+use super::success::Success;
+use super::welcome::menu_label;
+use super::{Context, Scene, Transition};
+use crate::door::{DoorUse, Doors};
+use crate::input::Action;
+use crate::item::Item;
+use crate::level::{Level, LevelSource};
+use crate::maze::{self, Hazard};
+use crate::pathfinding::{self, Cell, DistanceField};
+use crate::raycaster::{self, RayHit};
+use crate::renderer::Renderer;
+use crate::savegame::SavedGame;
+use crate::stats::Stats;
+use crate::textures::TextureSet;
+use crate::weapon::{self, Shot};
+use crate::trigger::TriggerAction;
+use crate::world::{self, World};
+use crate::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+const MAX_DEPTH: f32 = 20.0;
+// Paso fijo de la simulación; el dibujo va a su propio ritmo
+const FIXED_DT: f32 = 1.0 / 60.0;
+const MAX_FRAME_TIME: f32 = 0.25; // Evita la espiral de pasos tras una pausa larga
+const MINIMAP_SIZE: i32 = 150;
+const MINIMAP_SCALE: f32 = 10.0;
+const MINIMAP_RAY_STRIDE: usize = 20;
+const SCREENSHOT_DIR: &str = "screenshots";
+const DEFAULT_MUSIC: &str = "assets/background_music.mp3";
+const FLASH_TIME: f32 = 0.3; // Segundos que dura el destello al recibir daño
+const FLASH_STRENGTH: f32 = 0.5;
+
+// Un nivel en juego
+pub struct Playing {
+    level: Level,
+    source: LevelSource, // De dónde salió el nivel, para récords y partidas guardadas
+    world: World,        // Jugador, metas, objetos y enemigos
+    textures: TextureSet,
+    renderer: Renderer,
+    doors: Doors,
+    stats: Stats,              // Tiempo, puntaje y recorrido
+    accumulator: f32,          // Tiempo real todavía no simulado
+    show_path: bool,           // Pista: camino hasta la meta en el minimapa
+    step_timer: f32,           // Cooldown para pasos (evita spam de sonido)
+    hits: Vec<Option<RayHit>>, // Rayos del último cuadro, para el minimapa
+    hint: Option<Vec<Cell>>,
+    goal_field: Option<DistanceField>, // Distancias a las metas, al pedir la pista
+    // Paredes que cambiaron los disparadores, para la partida guardada
+    walls: Vec<(Cell, char)>,
+    flash_color: Color, // Destello de daño sobre el cuadro
+    flash_timer: f32,
+    cause: String, // Qué lo dañó por última vez, para la pantalla de fin
+}
+
+impl Playing {
+    // Carga el nivel; con `saved` continúa una partida guardada
+    pub fn start(
+        ctx: &mut Context,
+        source: LevelSource,
+        saved: Option<&SavedGame>,
+    ) -> Result<Playing, String> {
+        let level = source.load()?;
+        if let Some(i) = ctx.levels.iter().position(|p| source.file() == Some(p)) {
+            ctx.level_entries[i] = Ok(menu_label(&level));
+        }
+
+        // Texturas propias del nivel sobre las de siempre
+        let mut textures = world::load_textures()?;
+        for (ch, path) in &level.textures {
+            if let Err(e) = textures.insert_file(*ch, path) {
+                ctx.show_message(e, 2.0);
+            }
+        }
+
+        // Si falta el archivo se juega sin música
+        ctx.music = ctx
+            .audio
+            .new_music(level.music.as_deref().unwrap_or(DEFAULT_MUSIC))
+            .ok();
+        if let Some(music) = &ctx.music {
+            music.play_stream();
+        }
+
+        // Sonidos de los disparadores; si falta un archivo se sigue sin ese sonido
+        ctx.level_sounds.clear();
+        for trigger in &level.triggers {
+            let TriggerAction::Sound(path) = &trigger.action else { continue };
+            if ctx.level_sounds.contains_key(path) {
+                continue;
+            }
+            match ctx.audio.new_sound(path) {
+                Ok(sound) => {
+                    ctx.level_sounds.insert(path.clone(), sound);
+                }
+                Err(_) => ctx.show_message(format!("No se pudo cargar el sonido {path}"), 2.0),
+            }
+        }
+        ctx.apply_volumes();
+
+        let mut playing = Playing {
+            world: World::from_level(&level),
+            textures,
+            renderer: Renderer::new(MAX_DEPTH),
+            doors: Doors::new(&level.maze, &level.locks),
+            stats: Stats::new(&level),
+            accumulator: 0.0,
+            show_path: false,
+            step_timer: 0.0,
+            hits: Vec::new(),
+            hint: None,
+            goal_field: None,
+            walls: Vec::new(),
+            flash_color: Color::RED,
+            flash_timer: 0.0,
+            cause: String::new(),
+            level,
+            source,
+        };
+        if let Some(saved) = saved {
+            saved.apply(
+                &mut playing.world,
+                &mut playing.level.maze,
+                &mut playing.doors,
+                &mut playing.stats,
+            );
+            playing.walls = saved.walls.clone();
+        }
+        Ok(playing)
+    }
+
+    // Rayo por la columna central: le da al primer enemigo en pie antes de la pared
+    fn shoot(&mut self, ctx: &mut Context) {
+        let body = self.world.player_body();
+        let maze = &self.level.maze;
+        let wall = raycaster::cast_ray(maze, &self.doors, body.pos, body.angle, MAX_DEPTH)
+            .map_or(MAX_DEPTH, |hit| hit.distance);
+        if let Some(kind) = self.world.shoot(wall, weapon::DAMAGE) {
+            self.stats.score += kind.score();
+            ctx.show_message(format!("Derribaste a {}", kind.label()), 2.0);
+        }
+    }
+
+    // Aplica la acción de un disparador; algunas terminan el nivel
+    fn run_action(&mut self, ctx: &mut Context, action: TriggerAction) -> Option<Transition> {
+        match action {
+            TriggerAction::Message(text) => ctx.show_message(text, 3.0),
+            TriggerAction::OpenDoor(cell) => self.doors.open(cell),
+            TriggerAction::Sound(path) => {
+                if let Some(sound) = ctx.level_sounds.get(&path) {
+                    sound.play();
+                }
+            }
+            TriggerAction::Teleport { pos, angle } => {
+                let body = self.world.player_body_mut();
+                body.pos = pos;
+                body.velocity = Vector2::zero();
+                if let Some(angle) = angle {
+                    body.angle = angle;
+                }
+            }
+            TriggerAction::SetWall(cell, ch) => {
+                self.level.maze.set_wall(cell, ch);
+                self.walls.retain(|&(c, _)| c != cell);
+                self.walls.push((cell, ch));
+            }
+            TriggerAction::EndLevel => return Some(self.finish(ctx)),
+        }
+        None
+    }
+
+    // Nivel completado: récords y pantalla de resumen
+    fn finish(&mut self, ctx: &mut Context) -> Transition {
+        let mut new_record = false;
+        if let Some(id) = self.source.file() {
+            new_record = ctx.save_data.record(id, self.stats.time, self.stats.score);
+            if let Err(e) = ctx.save_data.save() {
+                ctx.show_message(format!("No se pudo guardar el progreso: {e}"), 5.0);
+            }
+        }
+        let stats = std::mem::take(&mut self.stats);
+        Transition::Replace(Box::new(Success::new(stats, new_record)))
+    }
+}
+
+impl Scene for Playing {
+    fn update(&mut self, ctx: &mut Context, dt: f32) -> Transition {
+        let input = &ctx.input;
+
+        // La simulación queda congelada mientras dura la pausa
+        if input.is_pressed(Action::Pause) {
+            if let Some(music) = &ctx.music {
+                music.pause_stream();
+            }
+            return Transition::Push(Box::new(Paused::new(self.source.clone())));
+        }
+
+        // Las opciones pueden haber cambiado en la pausa
+        self.world.player.mouse_sensitivity = ctx.settings.mouse_sensitivity;
+        self.world.player.fov = ctx.settings.fov.to_radians();
+
+        // Animación
+        self.world.animate(dt);
+
+        // Sonido de pasos simple con cooldown si hay movimiento
+        self.step_timer = (self.step_timer - dt).max(0.0);
+        if input.is_moving() && self.step_timer <= 0.0 {
+            ctx.step_sound.play();
+            self.step_timer = 0.25; // 4 pasos por segundo aprox.
+        }
+
+        // Abrir o cerrar la puerta de enfrente
+        if input.is_pressed(Action::Use) {
+            let (body, player) = (self.world.player_body(), &self.world.player);
+            let has_key = |key| player.inventory.has_key(key);
+            if let Some(DoorUse::Locked(key)) = self.doors.use_door(body.pos, body.angle, has_key) {
+                let text = format!("Esta puerta necesita la llave {}", key.label());
+                ctx.show_message(text, 2.0);
+            }
+        }
+
+        // Guardado y carga rápidos
+        if ctx.input.is_pressed(Action::QuickSave) {
+            let saved = SavedGame::capture(
+                &self.source,
+                &self.world,
+                &self.doors,
+                &self.walls,
+                &self.stats,
+            );
+            match saved.save(&ctx.saved_game_path) {
+                Ok(()) => ctx.show_message("Partida guardada", 2.0),
+                Err(e) => ctx.show_message(format!("No se pudo guardar la partida: {e}"), 2.0),
+            }
+        }
+        if ctx.input.is_pressed(Action::QuickLoad) {
+            let loaded = SavedGame::load(&ctx.saved_game_path).map_err(|e| e.to_string());
+            match loaded.and_then(|saved| Playing::start(ctx, saved.source.clone(), Some(&saved))) {
+                Ok(playing) => return Transition::Replace(Box::new(playing)),
+                Err(e) => ctx.show_message(format!("No se pudo cargar la partida: {e}"), 2.0),
+            }
+        }
+
+        // Sistemas del mundo en pasos fijos: control, IA, colisiones y después
+        // puertas, disparadores y daño
+        let input = &ctx.input;
+        let maze = &self.level.maze;
+        let mut actions = Vec::new();
+        self.world.look(input);
+        self.accumulator += dt.min(MAX_FRAME_TIME);
+        while self.accumulator >= FIXED_DT && !self.world.player.is_dead() {
+            self.world.control(input, FIXED_DT);
+            let hits = self.world.think(maze, &self.doors, FIXED_DT);
+            self.world.move_bodies(maze, &self.doors, FIXED_DT);
+            let pos = self.world.player_body().pos;
+            self.doors.update(FIXED_DT, &self.world.colliders());
+            self.stats.time += FIXED_DT;
+            self.stats.visit(pos);
+            actions.extend(self.world.step_triggers());
+            for (kind, amount) in hits {
+                self.world.player.damage(amount);
+                self.flash_color = Color::RED;
+                self.flash_timer = FLASH_TIME;
+                self.cause = format!("Te mató {}", kind.label());
+            }
+
+            // Las casillas peligrosas dañan mientras se está encima
+            let (x, y) = (pos.x.floor(), pos.y.floor());
+            if let Some(hazard) = maze.hazard(x as i32, y as i32) {
+                self.world.player.damage(hazard.damage() * FIXED_DT);
+                self.flash_color = hazard_color(hazard);
+                self.flash_timer = FLASH_TIME;
+                self.cause = format!("Moriste en {}", hazard.label());
+            }
+            self.accumulator -= FIXED_DT;
+        }
+        self.flash_timer = (self.flash_timer - dt).max(0.0);
+
+        if self.world.player.is_dead() {
+            ctx.music = None;
+            let reason = std::mem::take(&mut self.cause);
+            return Transition::Replace(Box::new(GameOver::new(self.source.clone(), reason)));
+        }
+
+        // Eventos del nivel
+        if ctx.input.is_pressed(Action::Use) {
+            actions.extend(self.world.use_triggers());
+        }
+        for action in actions {
+            if let Some(transition) = self.run_action(ctx, action) {
+                return transition;
+            }
+        }
+
+        // Disparar manteniendo apretado, al ritmo del arma
+        self.world.player.weapon.update(dt);
+        if ctx.input.is_down(Action::Fire) {
+            match self.world.player.weapon.fire() {
+                Some(Shot::Fired) => {
+                    ctx.fire_sound.play();
+                    self.shoot(ctx);
+                }
+                Some(Shot::Empty) if ctx.input.is_pressed(Action::Fire) => {
+                    ctx.show_message("Sin munición", 1.0)
+                }
+                _ => {}
+            }
+        }
+
+        // Recoger objetos al pasar por encima
+        for item in self.world.collect_pickups() {
+            self.world.player.pick_up(item);
+            self.stats.collect(item);
+            ctx.pickup_sound.play();
+            ctx.show_message(format!("Recogiste {}", item.label()), 2.0);
+        }
+
+        // Dibujar la escena en el framebuffer
+        let camera = self.world.camera();
+        self.hits = self.renderer.render(
+            &self.level.maze,
+            &self.doors,
+            &camera,
+            &self.textures,
+            &mut ctx.framebuffer,
+        );
+        self.renderer.render_sprites(
+            &self.world.sprites(),
+            &camera,
+            &self.textures,
+            &mut ctx.framebuffer,
+        );
+        if self.flash_timer > 0.0 {
+            let amount = self.flash_timer / FLASH_TIME * FLASH_STRENGTH;
+            ctx.framebuffer.flash(self.flash_color, amount);
+        }
+        self.world
+            .player
+            .weapon
+            .draw(&self.textures, &mut ctx.framebuffer);
+        ctx.frame_ready = true;
+
+        // Captura de pantalla
+        if ctx.input.is_pressed(Action::Screenshot) {
+            match ctx.framebuffer.save_screenshot(SCREENSHOT_DIR) {
+                Ok(path) => ctx.show_message(format!("Captura guardada: {path}"), 2.0),
+                Err(e) => ctx.show_message(e, 2.0),
+            }
+        }
+
+        if ctx.input.is_pressed(Action::ShowPath) {
+            self.show_path = !self.show_path;
+        }
+        // El camino sale de la celda del jugador: se rehace solo cuando cambia de celda
+        let cell = self.world.player_body().cell();
+        if !self.show_path {
+            self.hint = None;
+        } else if self.hint.as_ref().and_then(|path| path.first()) != cell.as_ref() {
+            let maze = &self.level.maze;
+            let field = self
+                .goal_field
+                .get_or_insert_with(|| pathfinding::distance_field(maze, maze.goals()));
+            self.hint = cell.and_then(|cell| field.path_from(maze, cell));
+        }
+
+        Transition::None
+    }
+
+    fn draw(&self, ctx: &Context, d: &mut RaylibDrawHandle) {
+        let level = &self.level;
+        let player = &self.world.player;
+        let body = self.world.player_body();
+        ctx.framebuffer.draw(d, SCREEN_WIDTH, SCREEN_HEIGHT);
+
+        // Minimap
+        let minimap_x = SCREEN_WIDTH - MINIMAP_SIZE - 10;
+        let minimap_y = 10;
+        d.draw_rectangle(
+            minimap_x,
+            minimap_y,
+            MINIMAP_SIZE,
+            MINIMAP_SIZE,
+            Color::new(0, 0, 0, 150),
+        );
+
+        for y in 0..level.maze.height() {
+            for x in 0..level.maze.width() {
+                if let Some(ch) = level.maze.get_wall(x as f32, y as f32) {
+                    let color = match ch {
+                        '#' => Color::GRAY,
+                        '+' => Color::RED,
+                        '-' => Color::GREEN,
+                        '|' => Color::BLUE,
+                        // Las puertas se aclaran a medida que se abren
+                        maze::DOOR_TILE => {
+                            let open = self.doors.get((x, y)).map_or(0.0, |door| door.open);
+                            Color::BROWN.fade(1.0 - open * 0.8)
+                        }
+                        _ => Color::WHITE,
+                    };
+                    d.draw_rectangle(
+                        minimap_x + (x as f32 * MINIMAP_SCALE) as i32,
+                        minimap_y + (y as f32 * MINIMAP_SCALE) as i32,
+                        MINIMAP_SCALE as i32,
+                        MINIMAP_SCALE as i32,
+                        color,
+                    );
+                } else if let Some(hazard) = level.maze.hazard(x as i32, y as i32) {
+                    d.draw_rectangle(
+                        minimap_x + (x as f32 * MINIMAP_SCALE) as i32,
+                        minimap_y + (y as f32 * MINIMAP_SCALE) as i32,
+                        MINIMAP_SCALE as i32,
+                        MINIMAP_SCALE as i32,
+                        hazard_color(hazard).fade(0.6),
+                    );
+                }
+            }
+        }
+
+        // Paredes visibles y cono de visión en el minimapa
+        let camera = self.world.camera();
+        let player_x = minimap_x as f32 + body.pos.x * MINIMAP_SCALE;
+        let player_y = minimap_y as f32 + body.pos.y * MINIMAP_SCALE;
+        let mut visible_cells = HashSet::new();
+        for (i, hit) in self.hits.iter().enumerate() {
+            let Some(hit) = hit else { continue };
+            visible_cells.insert(hit.cell);
+            if i % MINIMAP_RAY_STRIDE == 0 {
+                let angle = camera.ray_angle(i, self.hits.len());
+                d.draw_line_v(
+                    Vector2::new(player_x, player_y),
+                    Vector2::new(
+                        player_x + angle.cos() * hit.distance * MINIMAP_SCALE,
+                        player_y + angle.sin() * hit.distance * MINIMAP_SCALE,
+                    ),
+                    Color::new(255, 255, 0, 60),
+                );
+            }
+        }
+        for (x, y) in visible_cells {
+            d.draw_rectangle_lines(
+                minimap_x + (x as f32 * MINIMAP_SCALE) as i32,
+                minimap_y + (y as f32 * MINIMAP_SCALE) as i32,
+                MINIMAP_SCALE as i32,
+                MINIMAP_SCALE as i32,
+                Color::new(255, 255, 255, 120),
+            );
+        }
+
+        // Camino sugerido hasta la meta
+        if let Some(path) = &self.hint {
+            let center = |(x, y): Cell| {
+                Vector2::new(
+                    minimap_x as f32 + (x as f32 + 0.5) * MINIMAP_SCALE,
+                    minimap_y as f32 + (y as f32 + 0.5) * MINIMAP_SCALE,
+                )
+            };
+            for step in path.windows(2) {
+                d.draw_line_v(center(step[0]), center(step[1]), Color::ORANGE);
+            }
+        }
+
+        // Enemigos en el minimapa
+        for (enemy_body, enemy) in self.world.enemies() {
+            let color = if enemy.is_dead() {
+                Color::DARKGRAY
+            } else {
+                Color::RED
+            };
+            d.draw_circle(
+                minimap_x + (enemy_body.pos.x * MINIMAP_SCALE) as i32,
+                minimap_y + (enemy_body.pos.y * MINIMAP_SCALE) as i32,
+                3.0,
+                color,
+            );
+        }
+
+        // Jugador en el minimapa
+        d.draw_circle(
+            minimap_x + (body.pos.x * MINIMAP_SCALE) as i32,
+            minimap_y + (body.pos.y * MINIMAP_SCALE) as i32,
+            3.0,
+            Color::YELLOW,
+        );
+
+        // Dirección del jugador
+        d.draw_line(
+            minimap_x + (body.pos.x * MINIMAP_SCALE) as i32,
+            minimap_y + (body.pos.y * MINIMAP_SCALE) as i32,
+            minimap_x + ((body.pos.x + body.angle.cos() * 2.0) * MINIMAP_SCALE) as i32,
+            minimap_y + ((body.pos.y + body.angle.sin() * 2.0) * MINIMAP_SCALE) as i32,
+            Color::YELLOW,
+        );
+
+        // Estrella animada (meta) en minimapa
+        let star_texture = ctx.star_animation.current();
+        for &(x, y) in level.maze.goals() {
+            d.draw_texture_ex(
+                star_texture,
+                Vector2 {
+                    x: minimap_x as f32 + x as f32 * MINIMAP_SCALE,
+                    y: minimap_y as f32 + y as f32 * MINIMAP_SCALE,
+                },
+                0.0,
+                0.2,
+                Color::WHITE,
+            );
+        }
+
+        // FPS
+        d.draw_text(&format!("FPS: {}", ctx.fps), 10, 10, 20, Color::LIME);
+
+        // Nombre del nivel y tiempo
+        let stats = &self.stats;
+        let time_text = match level.par_time {
+            Some(par) => format!("{}  {:.1}s / {:.0}s", level.name, stats.time, par),
+            None => format!("{}  {:.1}s", level.name, stats.time),
+        };
+        d.draw_text(&time_text, 10, 35, 20, Color::WHITE);
+        d.draw_text(&format!("Puntos: {}", stats.score), 10, 60, 20, Color::GOLD);
+        let health_text = format!("Salud: {:.0}", player.health.ceil());
+        d.draw_text(&health_text, 10, 85, 20, Color::RED);
+        if player.armor > 0.0 {
+            let armor_text = format!("Armadura: {:.0}", player.armor.ceil());
+            d.draw_text(&armor_text, 10, 110, 20, Color::SKYBLUE);
+        }
+
+        // Mira en el centro y balas abajo a la derecha
+        let (center_x, center_y) = (SCREEN_WIDTH / 2, SCREEN_HEIGHT / 2);
+        d.draw_line(center_x - 6, center_y, center_x + 6, center_y, Color::WHITE);
+        d.draw_line(center_x, center_y - 6, center_x, center_y + 6, Color::WHITE);
+        let ammo_text = format!("Balas: {}", player.weapon.ammo);
+        let ammo_x = SCREEN_WIDTH - d.measure_text(&ammo_text, 20) - 10;
+        d.draw_text(&ammo_text, ammo_x, SCREEN_HEIGHT - 30, 20, Color::YELLOW);
+
+        // Inventario: una llave por objeto, sobre el mensaje
+        for (i, item) in player.inventory.items().iter().enumerate() {
+            let x = 20 + i as i32 * 40;
+            let y = SCREEN_HEIGHT - 60;
+            d.draw_rectangle(x - 8, y - 12, 36, 24, Color::new(0, 0, 0, 150));
+            if let Item::Key(color) = item {
+                d.draw_circle(x, y, 6.0, color.color());
+                d.draw_rectangle(x + 4, y - 2, 16, 4, color.color());
+                d.draw_rectangle(x + 14, y + 2, 3, 5, color.color());
+            }
+        }
+
+        if ctx.message_timer > 0.0 {
+            d.draw_text(&ctx.message, 10, SCREEN_HEIGHT - 30, 20, Color::WHITE);
+        }
+    }
+}
+
+// Color del destello y del minimapa para cada casilla peligrosa
+fn hazard_color(hazard: Hazard) -> Color {
+    match hazard {
+        Hazard::Lava => Color::ORANGE,
+        Hazard::Spikes => Color::MAROON,
+    }
+}
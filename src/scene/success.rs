@@ -0,0 +1,61 @@
+use raylib::prelude::*;
+use super::welcome::Welcome;
+use super::{Context, Scene, Transition};
+use crate::input::Action;
+use crate::stats::Stats;
+use crate::SCREEN_WIDTH;
+
+// Resumen del nivel terminado
+pub struct Success {
+    stats: Stats,
+    new_record: bool, // Nuevo mejor tiempo en esta partida
+}
+
+impl Success {
+    pub fn new(stats: Stats, new_record: bool) -> Self {
+        Success { stats, new_record }
+    }
+}
+
+impl Scene for Success {
+    fn update(&mut self, ctx: &mut Context, _dt: f32) -> Transition {
+        if ctx.input.is_pressed(Action::Confirm) {
+            return Transition::Reset(Box::new(Welcome::default()));
+        }
+        Transition::None
+    }
+
+    fn draw(&self, ctx: &Context, d: &mut RaylibDrawHandle) {
+        let stats = &self.stats;
+        d.draw_texture(&ctx.success_texture, 0, 0, Color::WHITE);
+        d.draw_text("¡NIVEL COMPLETADO!", SCREEN_WIDTH / 2 - 150, 100, 30, Color::GREEN);
+
+        // Resumen de la partida
+        let par_text = match stats.par_difference() {
+            Some(diff) if diff <= 0.0 => format!("  ({:.1}s bajo el par)", -diff),
+            Some(diff) => format!("  ({:.1}s sobre el par)", diff),
+            None => String::new(),
+        };
+        let lines = [
+            format!("Tiempo: {:.1}s{}", stats.time, par_text),
+            format!("Objetos: {} / {}", stats.collected, stats.total_items),
+            format!("Puntos: {}", stats.score),
+            format!("Explorado: {:.0}%", stats.explored_percent()),
+        ];
+        for (i, line) in lines.iter().enumerate() {
+            let y = 180 + i as i32 * 40;
+            d.draw_text(line, SCREEN_WIDTH / 2 - 180, y, 25, Color::WHITE);
+        }
+        if self.new_record {
+            let x = SCREEN_WIDTH / 2 - 80;
+            d.draw_text("¡Nuevo récord!", x, 340, 25, Color::GOLD);
+        }
+        d.draw_text(
+            "Presiona ENTER para volver al menú",
+            SCREEN_WIDTH / 2 - 180,
+            400,
+            20,
+            Color::WHITE,
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use raylib::prelude::*;
+use super::playing::Playing;
+use super::{Context, Scene, Transition};
+use crate::input::Action;
+use crate::level::{Level, LevelSource, RANDOM_LEVEL_SIZE};
+use crate::maze::generate::Algorithm;
+use crate::savegame::SavedGame;
+use crate::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+// Menú principal: niveles, el aleatorio y "continuar" si hay partida guardada
+#[derive(Default)]
+pub struct Welcome {
+    selected: usize,
+    has_saved_game: bool,
+}
+
+impl Scene for Welcome {
+    fn update(&mut self, ctx: &mut Context, _dt: f32) -> Transition {
+        // ESC en el menú principal cierra el juego
+        if ctx.input.is_pressed(Action::Pause) {
+            return Transition::Quit;
+        }
+
+        let levels = ctx.levels.len();
+        self.has_saved_game = ctx.saved_game_path.exists();
+        let menu_len = levels + 1 + usize::from(self.has_saved_game);
+        if ctx.input.is_pressed(Action::MenuUp) {
+            self.selected = if self.selected > 0 {
+                self.selected - 1
+            } else {
+                menu_len - 1
+            };
+        }
+        if ctx.input.is_pressed(Action::MenuDown) {
+            self.selected = (self.selected + 1) % menu_len;
+        }
+        self.selected = self.selected.min(menu_len - 1);
+
+        if !ctx.input.is_pressed(Action::Confirm) {
+            return Transition::None;
+        }
+
+        // Se vuelve a leer por si el archivo cambió; con errores no se entra.
+        // El nivel aleatorio genera un laberinto nuevo cada vez.
+        let (source, saved) = if let Some(path) = ctx.levels.get(self.selected) {
+            (LevelSource::File(path.clone()), None)
+        } else if self.selected == levels {
+            let seed = ctx.time.to_bits() ^ std::process::id() as u64;
+            let algorithm = Algorithm::ALL[seed as usize % Algorithm::ALL.len()];
+            let source = LevelSource::Random {
+                algorithm,
+                seed,
+                size: RANDOM_LEVEL_SIZE,
+            };
+            (source, None)
+        } else {
+            match SavedGame::load(&ctx.saved_game_path) {
+                Ok(saved) => (saved.source.clone(), Some(saved)),
+                Err(e) => {
+                    ctx.show_message(format!("No se pudo continuar la partida: {e}"), 3.0);
+                    return Transition::None;
+                }
+            }
+        };
+
+        match Playing::start(ctx, source, saved.as_ref()) {
+            Ok(playing) => Transition::Replace(Box::new(playing)),
+            Err(e) if saved.is_some() => {
+                ctx.show_message(format!("No se pudo continuar la partida: {e}"), 3.0);
+                Transition::None
+            }
+            Err(e) => {
+                if let Some(entry) = ctx.level_entries.get_mut(self.selected) {
+                    *entry = Err(e);
+                }
+                Transition::None
+            }
+        }
+    }
+
+    fn draw(&self, ctx: &Context, d: &mut RaylibDrawHandle) {
+        d.draw_texture(&ctx.welcome_texture, 0, 0, Color::WHITE);
+        d.draw_text("DOOM ARCAICO", SCREEN_WIDTH / 2 - 150, 100, 40, Color::RED);
+        d.draw_text(
+            "Selecciona un nivel:",
+            SCREEN_WIDTH / 2 - 120,
+            200,
+            20,
+            Color::WHITE,
+        );
+
+        let levels = ctx.levels.len();
+        for (i, entry) in ctx.level_entries.iter().enumerate() {
+            let (label, color) = match entry {
+                Ok(name) => (name.as_str(), Color::WHITE),
+                Err(_) => (ctx.levels[i].as_str(), Color::RED),
+            };
+            let color = if i == self.selected {
+                Color::YELLOW
+            } else {
+                color
+            };
+            let label = format!("Nivel {}: {}", i + 1, label);
+            let y = 250 + i as i32 * 30;
+            d.draw_text(&label, SCREEN_WIDTH / 2 - 250, y, 20, color);
+
+            // Récord a la derecha del nombre
+            let Some(record) = ctx.save_data.get(&ctx.levels[i]) else { continue };
+            let best = match record.best_time {
+                Some(time) => format!("{time:.1}s - {} pts", record.best_score),
+                None => format!("{} pts", record.best_score),
+            };
+            let x = SCREEN_WIDTH / 2 - 235 + d.measure_text(&label, 20);
+            d.draw_text(&best, x, y + 3, 16, Color::GREEN);
+        }
+
+        let mut extra = vec!["Nivel aleatorio"];
+        if self.has_saved_game {
+            extra.push("Continuar partida guardada");
+        }
+        for (i, label) in extra.into_iter().enumerate() {
+            let color = if self.selected == levels + i {
+                Color::YELLOW
+            } else {
+                Color::WHITE
+            };
+            let y = 250 + (levels + i) as i32 * 30;
+            d.draw_text(label, SCREEN_WIDTH / 2 - 250, y, 20, color);
+        }
+
+        // Por qué no se puede jugar el nivel elegido
+        if let Some(Err(e)) = ctx.level_entries.get(self.selected) {
+            d.draw_text(e, 20, SCREEN_HEIGHT - 110, 16, Color::RED);
+        }
+        if ctx.message_timer > 0.0 {
+            d.draw_text(&ctx.message, 20, SCREEN_HEIGHT - 80, 16, Color::ORANGE);
+        }
+
+        d.draw_text(
+            "Presiona ARRIBA/ABAJO para seleccionar nivel",
+            SCREEN_WIDTH / 2 - 200,
+            400,
+            20,
+            Color::WHITE,
+        );
+        d.draw_text(
+            "Presiona ENTER para comenzar",
+            SCREEN_WIDTH / 2 - 150,
+            430,
+            20,
+            Color::WHITE,
+        );
+        d.draw_text(
+            "Presiona ESC para salir",
+            SCREEN_WIDTH / 2 - 120,
+            460,
+            20,
+            Color::WHITE,
+        );
+    }
+}
+
+// Nombre del nivel con su dificultad, para el menú
+pub fn menu_label(level: &Level) -> String {
+    match level.difficulty() {
+        Some(score) => format!("{} (dificultad {score})", level.name),
+        None => level.name.clone(),
+    }
+}
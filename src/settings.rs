@@ -0,0 +1,380 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::save;
+
+// Resoluciones internas del raycaster que se pueden elegir
+pub const RESOLUTIONS: [(usize, usize); 5] =
+    [(200, 150), (320, 240), (400, 300), (640, 480), (800, 600)];
+// Límites de cuadros por segundo; 0 es sin límite
+pub const FPS_LIMITS: [u32; 5] = [30, 60, 120, 144, 0];
+
+// Preferencias del jugador que se guardan entre partidas
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub mouse_sensitivity: f32, // Radianes por pixel
+    pub fov: f32,               // Grados
+    pub music_volume: f32,      // 0..1
+    pub sfx_volume: f32,        // 0..1
+    pub resolution: usize,      // Índice en RESOLUTIONS
+    pub fullscreen: bool,
+    pub fps_limit: u32, // Uno de FPS_LIMITS
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            mouse_sensitivity: 0.003,
+            fov: 60.0,
+            music_volume: 0.7,
+            sfx_volume: 1.0,
+            resolution: 2,
+            fullscreen: false,
+            fps_limit: 60,
+        }
+    }
+}
+
+// Una fila de la pantalla de opciones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    MouseSensitivity,
+    Fov,
+    MusicVolume,
+    SfxVolume,
+    Resolution,
+    Fullscreen,
+    FpsLimit,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 7] = [
+        Setting::MouseSensitivity,
+        Setting::Fov,
+        Setting::MusicVolume,
+        Setting::SfxVolume,
+        Setting::Resolution,
+        Setting::Fullscreen,
+        Setting::FpsLimit,
+    ];
+
+    // Nombre en el archivo de configuración
+    pub fn name(self) -> &'static str {
+        match self {
+            Setting::MouseSensitivity => "mouse_sensitivity",
+            Setting::Fov => "fov",
+            Setting::MusicVolume => "music_volume",
+            Setting::SfxVolume => "sfx_volume",
+            Setting::Resolution => "resolution",
+            Setting::Fullscreen => "fullscreen",
+            Setting::FpsLimit => "fps_limit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Setting> {
+        Setting::ALL.into_iter().find(|s| s.name() == name)
+    }
+
+    // Nombre en pantalla
+    pub fn label(self) -> &'static str {
+        match self {
+            Setting::MouseSensitivity => "Sensibilidad del mouse",
+            Setting::Fov => "Campo de visión",
+            Setting::MusicVolume => "Volumen de la música",
+            Setting::SfxVolume => "Volumen de efectos",
+            Setting::Resolution => "Resolución interna",
+            Setting::Fullscreen => "Pantalla completa",
+            Setting::FpsLimit => "Límite de FPS",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(io::Error),
+    UnknownSetting { line: usize, setting: String },
+    InvalidValue { line: usize, value: String },
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SettingsError::Io(e) => write!(f, "{e}"),
+            SettingsError::UnknownSetting { line, setting } => {
+                write!(f, "línea {line}: opción desconocida '{setting}'")
+            }
+            SettingsError::InvalidValue { line, value } => {
+                write!(f, "línea {line}: valor inválido '{value}'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl From<io::Error> for SettingsError {
+    fn from(e: io::Error) -> Self {
+        SettingsError::Io(e)
+    }
+}
+
+impl Settings {
+    // Sin archivo se usan los valores por defecto
+    pub fn load(path: &str) -> Result<Settings, SettingsError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Settings::parse(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Settings::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // Formato: una opción por línea seguida de su valor; '#' empieza un comentario.
+    //   fov 75
+    //   fullscreen true
+    pub fn parse(text: &str) -> Result<Settings, SettingsError> {
+        let mut settings = Settings::default();
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let line = line.split('#').next().unwrap_or("");
+            let mut words = line.split_whitespace();
+            let Some(key) = words.next() else { continue };
+            let value = words.next().unwrap_or("");
+
+            let setting = Setting::from_name(key).ok_or_else(|| SettingsError::UnknownSetting {
+                line: number,
+                setting: key.to_string(),
+            })?;
+            if !settings.set(setting, value) {
+                return Err(SettingsError::InvalidValue {
+                    line: number,
+                    value: value.to_string(),
+                });
+            }
+        }
+        Ok(settings)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), SettingsError> {
+        save::write_file(Path::new(path), &self.to_text())?;
+        Ok(())
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::from(
+            "# Opciones del juego; se reescribe al salir de la pantalla de opciones\n",
+        );
+        for setting in Setting::ALL {
+            let value = match setting {
+                Setting::MouseSensitivity => self.mouse_sensitivity.to_string(),
+                Setting::Fov => self.fov.to_string(),
+                Setting::MusicVolume => self.music_volume.to_string(),
+                Setting::SfxVolume => self.sfx_volume.to_string(),
+                Setting::Resolution => {
+                    let (width, height) = self.resolution_size();
+                    format!("{width}x{height}")
+                }
+                Setting::Fullscreen => self.fullscreen.to_string(),
+                Setting::FpsLimit => self.fps_limit.to_string(),
+            };
+            text += &format!("{} {value}\n", setting.name());
+        }
+        text
+    }
+
+    // Cambia una opción desde su texto; false si el valor no sirve
+    fn set(&mut self, setting: Setting, value: &str) -> bool {
+        let number = value.parse::<f32>().ok().filter(|v| v.is_finite());
+        match setting {
+            Setting::MouseSensitivity => match number.filter(|&v| v > 0.0) {
+                Some(v) => self.mouse_sensitivity = v,
+                None => return false,
+            },
+            Setting::Fov => match number.filter(|v| (30.0..=120.0).contains(v)) {
+                Some(v) => self.fov = v,
+                None => return false,
+            },
+            Setting::MusicVolume | Setting::SfxVolume => {
+                let Some(v) = number.filter(|v| (0.0..=1.0).contains(v)) else {
+                    return false;
+                };
+                if setting == Setting::MusicVolume {
+                    self.music_volume = v;
+                } else {
+                    self.sfx_volume = v;
+                }
+            }
+            Setting::Resolution => {
+                let size = value
+                    .split_once('x')
+                    .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)));
+                match RESOLUTIONS.iter().position(|&r| Some(r) == size) {
+                    Some(index) => self.resolution = index,
+                    None => return false,
+                }
+            }
+            Setting::Fullscreen => match value.parse() {
+                Ok(v) => self.fullscreen = v,
+                Err(_) => return false,
+            },
+            Setting::FpsLimit => match value.parse().ok().filter(|v| FPS_LIMITS.contains(v)) {
+                Some(v) => self.fps_limit = v,
+                None => return false,
+            },
+        }
+        true
+    }
+
+    // Mueve la opción un paso hacia arriba (1) o hacia abajo (-1)
+    pub fn adjust(&mut self, setting: Setting, direction: i32) {
+        let step = direction as f32;
+        match setting {
+            Setting::MouseSensitivity => {
+                self.mouse_sensitivity =
+                    (self.mouse_sensitivity + step * 0.0005).clamp(0.0005, 0.01)
+            }
+            Setting::Fov => self.fov = (self.fov + step * 5.0).clamp(30.0, 120.0),
+            Setting::MusicVolume => self.music_volume = volume_step(self.music_volume, step),
+            Setting::SfxVolume => self.sfx_volume = volume_step(self.sfx_volume, step),
+            Setting::Resolution => {
+                self.resolution = self
+                    .resolution
+                    .saturating_add_signed(direction as isize)
+                    .min(RESOLUTIONS.len() - 1)
+            }
+            Setting::Fullscreen => self.fullscreen = !self.fullscreen,
+            Setting::FpsLimit => {
+                let current = FPS_LIMITS.iter().position(|&v| v == self.fps_limit);
+                let index = (current.unwrap_or(0) as isize + direction as isize)
+                    .clamp(0, FPS_LIMITS.len() as isize - 1);
+                self.fps_limit = FPS_LIMITS[index as usize];
+            }
+        }
+    }
+
+    // Valor como se muestra en la pantalla de opciones
+    pub fn value_text(&self, setting: Setting) -> String {
+        match setting {
+            Setting::MouseSensitivity => format!("{:.4}", self.mouse_sensitivity),
+            Setting::Fov => format!("{:.0} grados", self.fov),
+            Setting::MusicVolume => format!("{:.0}%", self.music_volume * 100.0),
+            Setting::SfxVolume => format!("{:.0}%", self.sfx_volume * 100.0),
+            Setting::Resolution => {
+                let (width, height) = self.resolution_size();
+                format!("{width}x{height}")
+            }
+            Setting::Fullscreen => if self.fullscreen { "sí" } else { "no" }.to_string(),
+            Setting::FpsLimit if self.fps_limit == 0 => "sin límite".to_string(),
+            Setting::FpsLimit => self.fps_limit.to_string(),
+        }
+    }
+
+    pub fn resolution_size(&self) -> (usize, usize) {
+        RESOLUTIONS[self.resolution.min(RESOLUTIONS.len() - 1)]
+    }
+}
+
+// Volumen en décimos justos, para que el archivo no acumule errores de redondeo
+fn volume_step(volume: f32, step: f32) -> f32 {
+    ((volume * 10.0).round() + step).clamp(0.0, 10.0) / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_round_trip() {
+        let settings = Settings {
+            mouse_sensitivity: 0.0045,
+            fov: 85.0,
+            music_volume: 0.3,
+            sfx_volume: 0.0,
+            resolution: 4,
+            fullscreen: true,
+            fps_limit: 0,
+        };
+        let text = settings.to_text();
+        assert!(text.contains("resolution 800x600\n"));
+        assert_eq!(Settings::parse(&text).unwrap(), settings);
+        assert_eq!(Settings::parse("").unwrap(), Settings::default());
+    }
+
+    #[test]
+    fn adjust_stays_in_range() {
+        let mut settings = Settings::default();
+        for _ in 0..40 {
+            settings.adjust(Setting::Fov, 1);
+            settings.adjust(Setting::MusicVolume, 1);
+            settings.adjust(Setting::SfxVolume, -1);
+            settings.adjust(Setting::Resolution, 1);
+            settings.adjust(Setting::FpsLimit, 1);
+            settings.adjust(Setting::MouseSensitivity, -1);
+        }
+        assert_eq!(settings.fov, 120.0);
+        assert_eq!(settings.music_volume, 1.0);
+        assert_eq!(settings.sfx_volume, 0.0);
+        assert_eq!(settings.resolution, RESOLUTIONS.len() - 1);
+        assert_eq!(settings.fps_limit, 0);
+        assert_eq!(settings.mouse_sensitivity, 0.0005);
+
+        // Hacia abajo el índice se queda en el primero, sin dar la vuelta
+        for _ in 0..40 {
+            settings.adjust(Setting::Fov, -1);
+            settings.adjust(Setting::Resolution, -1);
+            settings.adjust(Setting::FpsLimit, -1);
+        }
+        assert_eq!(settings.fov, 30.0);
+        assert_eq!(settings.resolution, 0);
+        assert_eq!(settings.fps_limit, FPS_LIMITS[0]);
+    }
+
+    #[test]
+    fn volume_steps_are_exact_tenths() {
+        let mut settings = Settings::default();
+        settings.adjust(Setting::MusicVolume, -1);
+        settings.adjust(Setting::MusicVolume, -1);
+        assert_eq!(settings.music_volume, 0.5);
+        assert_eq!(settings.to_text().lines().nth(3), Some("music_volume 0.5"));
+    }
+
+    #[test]
+    fn set_rejects_out_of_range_values() {
+        let mut settings = Settings::default();
+        for (setting, value) in [
+            (Setting::Fov, "29"),
+            (Setting::Fov, "121"),
+            (Setting::MusicVolume, "1.5"),
+            (Setting::SfxVolume, "-0.1"),
+            (Setting::MouseSensitivity, "0"),
+            (Setting::MouseSensitivity, "inf"),
+            (Setting::Resolution, "1024x768"),
+            (Setting::Resolution, "640"),
+            (Setting::Fullscreen, "sí"),
+            (Setting::FpsLimit, "75"),
+        ] {
+            assert!(!settings.set(setting, value), "{} {value}", setting.name());
+        }
+        assert_eq!(settings, Settings::default());
+        assert!(settings.set(Setting::Resolution, "320x240"));
+        assert_eq!(settings.resolution, 1);
+    }
+
+    #[test]
+    fn reports_the_line_of_bad_entries() {
+        match Settings::parse("fov 70\nbrillo 3\n") {
+            Err(SettingsError::UnknownSetting { line: 2, setting }) => {
+                assert_eq!(setting, "brillo")
+            }
+            other => panic!("se esperaba una opción desconocida: {:?}", other.err()),
+        }
+        match Settings::parse("# comentario\nfov amplio\n") {
+            Err(SettingsError::InvalidValue { line: 2, value }) => assert_eq!(value, "amplio"),
+            other => panic!("se esperaba un valor inválido: {:?}", other.err()),
+        }
+        assert!(matches!(
+            Settings::parse("fullscreen\n"),
+            Err(SettingsError::InvalidValue { line: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,21 @@
+use crate::animation::Animation;
+
+// Billboard que siempre mira hacia la cámara; la posición la da la entidad
+pub struct Sprite {
+    pub scale: f32, // 1.0 = alto de una pared
+    pub animation: Animation<&'static str>,
+}
+
+impl Sprite {
+    pub fn new(scale: f32, animation: Animation<&'static str>) -> Self {
+        Sprite { scale, animation }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.animation.update(dt);
+    }
+
+    pub fn image_name(&self) -> &'static str {
+        self.animation.current()
+    }
+}
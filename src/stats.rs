@@ -0,0 +1,95 @@
+use raylib::prelude::*;
+use crate::item::Item;
+use crate::level::Level;
+use crate::pathfinding;
+
+// Datos de la partida en curso, para el HUD y la pantalla de nivel completado
+#[derive(Default)]
+pub struct Stats {
+    pub time: f32, // Segundos en el nivel
+    pub score: u32,
+    pub collected: usize,
+    pub total_items: usize,
+    pub par_time: Option<f32>,
+    pub visited: Vec<bool>, // Celdas por las que pasó el jugador, fila por fila
+    width: usize,
+    reachable: usize, // Celdas libres alcanzables desde el inicio
+}
+
+// Solo monedas y estrellas cuentan para el total de objetos del nivel
+fn counts(item: Item) -> bool {
+    matches!(item, Item::Coin | Item::Star)
+}
+
+impl Stats {
+    pub fn new(level: &Level) -> Self {
+        let width = level.maze.width();
+        let field = pathfinding::distance_field(&level.maze, &[level.spawn_cell()]);
+        let total_items = level.items.iter().filter(|entry| counts(entry.1)).count();
+        Stats {
+            time: 0.0,
+            score: 0,
+            collected: 0,
+            total_items,
+            par_time: level.par_time,
+            width,
+            visited: vec![false; width * level.maze.height()],
+            reachable: field.reached(),
+        }
+    }
+
+    pub fn visit(&mut self, pos: Vector2) {
+        if pos.x < 0.0 || pos.y < 0.0 || pos.x as usize >= self.width {
+            return;
+        }
+        let index = pos.y as usize * self.width + pos.x as usize;
+        if let Some(cell) = self.visited.get_mut(index) {
+            *cell = true;
+        }
+    }
+
+    pub fn collect(&mut self, item: Item) {
+        if counts(item) {
+            self.collected += 1;
+        }
+        self.score += item.score();
+    }
+
+    // Porcentaje de las celdas alcanzables que se recorrieron
+    pub fn explored_percent(&self) -> f32 {
+        if self.reachable == 0 {
+            return 0.0;
+        }
+        let visited = self.visited.iter().filter(|&&v| v).count();
+        (visited as f32 / self.reachable as f32 * 100.0).min(100.0)
+    }
+
+    // Segundos por debajo (negativo) o por encima del tiempo de referencia
+    pub fn par_difference(&self) -> Option<f32> {
+        self.par_time.map(|par| self.time - par)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::KeyColor;
+
+    #[test]
+    fn only_coins_and_stars_count_as_items() {
+        let level = Level::parse(
+            "@coin 2 1\n@star 3 1\n@key 2 1 red\n@health 3 1\n@ammo 2 1\n#####\n#P G#\n#####\n",
+        )
+        .unwrap();
+        let mut stats = Stats::new(&level);
+        assert_eq!(stats.total_items, 2);
+
+        for &(_, item) in &level.items {
+            stats.collect(item);
+        }
+        assert_eq!(stats.collected, 2);
+        assert_eq!(stats.score, 60);
+        stats.collect(Item::Key(KeyColor::Blue));
+        assert_eq!(stats.collected, stats.total_items);
+    }
+}
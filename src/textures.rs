@@ -1,74 +1,140 @@
 use raylib::prelude::*;
 use std::collections::HashMap;
-use std::slice;
+use std::fs;
 
-pub struct TextureManager {
-    images: HashMap<char, Image>,       // Acceso a pixeles
-    textures: HashMap<char, Texture2D>, // Texturas en GPU
+// Paredes que todos los niveles pueden usar sin declarar @texture
+pub const DEFAULT_WALLS: [(char, &str); 4] = [
+    ('+', "assets/wall4.png"),
+    ('-', "assets/wall2.png"),
+    ('|', "assets/wall1.png"),
+    ('#', "assets/wall3.png"),
+];
+
+// Imagen en memoria de CPU, lista para muestrear pixeles sin contexto de GPU
+pub struct TextureImage {
+    pub width: u32,
+    pub height: u32,
+    pixels: Vec<Color>,
 }
 
-impl TextureManager {
-    pub fn new(rl: &mut RaylibHandle, thread: &RaylibThread) -> Self {
-        let mut images = HashMap::new();
-        let mut textures = HashMap::new();
-
-        //Definir imagenes que se utilizaran
-        let texture_files = vec![
-            ('+', "assets/wall4.png"),
-            ('-', "assets/wall2.png"),
-            ('|', "assets/wall1.png"),
-            ('#', "assets/wall3.png"),
-        ];
-
-        for (ch, path) in texture_files {
-            let image = Image::load_image(path).expect(&format!("No se pudo cargar la imagen {path}"));
-            let texture = rl.load_texture(thread, path).expect(&format!("No se pudo cargar la textura {path}"));
-            images.insert(ch, image);
-            textures.insert(ch, texture);
-        }
+impl TextureImage {
+    pub fn from_png(bytes: &[u8]) -> Result<Self, String> {
+        let image = Image::load_image_from_mem(".png", bytes).map_err(|e| e.to_string())?;
+        Ok(TextureImage {
+            width: image.width as u32,
+            height: image.height as u32,
+            pixels: image.get_image_data().to_vec(),
+        })
+    }
+
+    // Vuelve transparente el fondo liso que toca los bordes (relleno por inundación
+    // desde las orillas), para imágenes sin canal alfa como las estrellas
+    pub fn clear_border_background(&mut self, tolerance: u8) {
+        let (w, h) = (self.width as usize, self.height as usize);
+        let key = self.pixels[0];
+        let matches = |c: Color| {
+            c.r.abs_diff(key.r) <= tolerance
+                && c.g.abs_diff(key.g) <= tolerance
+                && c.b.abs_diff(key.b) <= tolerance
+        };
+
+        let mut visited = vec![false; w * h];
+        let mut pending: Vec<usize> = (0..w)
+            .flat_map(|x| [x, (h - 1) * w + x])
+            .chain((0..h).flat_map(|y| [y * w, y * w + w - 1]))
+            .collect();
 
-        TextureManager { images, textures }
+        while let Some(i) = pending.pop() {
+            if visited[i] || !matches(self.pixels[i]) {
+                continue;
+            }
+            visited[i] = true;
+            self.pixels[i].a = 0;
+
+            let (x, y) = (i % w, i / w);
+            if x > 0 {
+                pending.push(i - 1);
+            }
+            if x + 1 < w {
+                pending.push(i + 1);
+            }
+            if y > 0 {
+                pending.push(i - w);
+            }
+            if y + 1 < h {
+                pending.push(i + w);
+            }
+        }
     }
 
-    pub fn get_pixel_color(&self, ch: char, tx: u32, ty: u32) -> Color {
-        if let Some(image) = self.images.get(&ch) {
-            let x = tx.min(image.width as u32 - 1) as i32;
-            let y = ty.min(image.height as u32 - 1) as i32;
-            get_pixel_color(image, x, y)
-        } else {
-            Color::WHITE
+    // Imagen cuadrada de `size` pixeles con el color que da `pixel(x, y)`
+    pub fn generate(size: usize, pixel: impl Fn(usize, usize) -> Color) -> Self {
+        let pixels = (0..size * size)
+            .map(|i| pixel(i % size, i / size))
+            .collect();
+        TextureImage {
+            width: size as u32,
+            height: size as u32,
+            pixels,
         }
     }
 
-    pub fn get_texture(&self, ch: char) -> Option<&Texture2D> {
-        self.textures.get(&ch)
+    pub fn load(path: &str) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| format!("No se pudo leer {path}: {e}"))?;
+        TextureImage::from_png(&bytes)
+            .map_err(|e| format!("No se pudo cargar la imagen {path}: {e}"))
     }
 
-    pub fn get_image(&self, ch: char) -> Option<&Image> {
-        self.images.get(&ch)
+    pub fn get_pixel_color(&self, tx: u32, ty: u32) -> Color {
+        let x = tx.min(self.width - 1) as usize;
+        let y = ty.min(self.height - 1) as usize;
+        self.pixels[y * self.width as usize + x]
     }
 }
 
-fn get_pixel_color(image: &Image, x: i32, y: i32) -> Color {
-    let width = image.width as usize;
-    let height = image.height as usize;
+pub struct TextureSet {
+    images: HashMap<char, TextureImage>,          // Paredes, suelo y techo
+    sprites: HashMap<&'static str, TextureImage>, // Billboards
+}
+
+impl TextureSet {
+    pub fn new() -> Self {
+        TextureSet {
+            images: HashMap::new(),
+            sprites: HashMap::new(),
+        }
+    }
+
+    // Paredes que todos los niveles pueden usar sin declarar @texture
+    pub fn load_default() -> Result<Self, String> {
+        let mut set = TextureSet::new();
+        for (ch, path) in DEFAULT_WALLS {
+            set.insert_file(ch, path)?;
+        }
+        Ok(set)
+    }
 
-    if x < 0 || y < 0 || x as usize >= width || y as usize >= height {
-        return Color::WHITE;
+    // Textura de pared/suelo para la letra `ch` (reemplaza la que tenga)
+    pub fn insert(&mut self, ch: char, image: TextureImage) {
+        self.images.insert(ch, image);
     }
 
-    let x = x as usize;
-    let y = y as usize;
-    let data_len = width * height * 4;
+    // Textura de pared/suelo desde un archivo (reemplaza la que tenga la letra)
+    pub fn insert_file(&mut self, ch: char, path: &str) -> Result<(), String> {
+        self.insert(ch, TextureImage::load(path)?);
+        Ok(())
+    }
 
-    unsafe {
-        let data = slice::from_raw_parts(image.data as *const u8, data_len);
-        let idx = (y * width + x) * 4;
+    // Imagen de billboard con el nombre que usan las animaciones
+    pub fn insert_sprite(&mut self, name: &'static str, image: TextureImage) {
+        self.sprites.insert(name, image);
+    }
 
-        if idx + 3 >= data_len {
-            return Color::WHITE;
-        }
+    pub fn get(&self, ch: char) -> Option<&TextureImage> {
+        self.images.get(&ch)
+    }
 
-        Color::new(data[idx], data[idx + 1], data[idx + 2], data[idx + 3])
+    pub fn sprite(&self, name: &str) -> Option<&TextureImage> {
+        self.sprites.get(name)
     }
 }
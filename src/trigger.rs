@@ -0,0 +1,148 @@
+use raylib::prelude::*;
+use crate::pathfinding::Cell;
+
+// Cuándo reacciona una zona
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerEvent {
+    Enter, // Al entrar el jugador
+    Exit,  // Al salir
+    Use,   // Al presionar usar estando adentro
+}
+
+impl TriggerEvent {
+    pub const ALL: [TriggerEvent; 3] = [TriggerEvent::Enter, TriggerEvent::Exit, TriggerEvent::Use];
+
+    // Nombre en los archivos de nivel
+    pub fn name(self) -> &'static str {
+        match self {
+            TriggerEvent::Enter => "enter",
+            TriggerEvent::Exit => "exit",
+            TriggerEvent::Use => "use",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<TriggerEvent> {
+        TriggerEvent::ALL.into_iter().find(|e| e.name() == name)
+    }
+}
+
+// Lo que pasa cuando se dispara
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriggerAction {
+    Message(String),
+    OpenDoor(Cell),
+    Sound(String), // Archivo de sonido
+    Teleport { pos: Vector2, angle: Option<f32> },
+    SetWall(Cell, char), // Cambia la textura de una pared
+    EndLevel,
+}
+
+// Zona rectangular de celdas del nivel con su evento y su acción
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trigger {
+    pub area: (Cell, Cell), // Esquinas opuestas, incluidas
+    pub event: TriggerEvent,
+    pub action: TriggerAction,
+    inside: bool, // El jugador estaba adentro en el paso anterior
+}
+
+impl Trigger {
+    pub fn new(area: (Cell, Cell), event: TriggerEvent, action: TriggerAction) -> Self {
+        let ((x1, y1), (x2, y2)) = area;
+        Trigger {
+            area: ((x1.min(x2), y1.min(y2)), (x1.max(x2), y1.max(y2))),
+            event,
+            action,
+            inside: false,
+        }
+    }
+
+    pub fn contains(&self, (x, y): Cell) -> bool {
+        let ((x1, y1), (x2, y2)) = self.area;
+        (x1..=x2).contains(&x) && (y1..=y2).contains(&y)
+    }
+
+    // Centro de la zona, en coordenadas del mundo
+    pub fn center(&self) -> Vector2 {
+        let ((x1, y1), (x2, y2)) = self.area;
+        Vector2::new((x1 + x2 + 1) as f32 / 2.0, (y1 + y2 + 1) as f32 / 2.0)
+    }
+
+    // Un paso con el jugador en `cell`; true si entró o salió según el evento
+    pub fn update(&mut self, cell: Option<Cell>) -> bool {
+        let inside = cell.is_some_and(|cell| self.contains(cell));
+        let fired = match self.event {
+            TriggerEvent::Enter => inside && !self.inside,
+            TriggerEvent::Exit => !inside && self.inside,
+            TriggerEvent::Use => false,
+        };
+        self.inside = inside;
+        fired
+    }
+
+    // Deja la zona como si el jugador ya estuviera en `cell`, sin disparar nada
+    pub fn settle(&mut self, cell: Option<Cell>) {
+        self.inside = cell.is_some_and(|cell| self.contains(cell));
+    }
+
+    // Presionar usar solo dispara las zonas "use" donde está el jugador
+    pub fn is_usable(&self) -> bool {
+        self.event == TriggerEvent::Use && self.inside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(event: TriggerEvent) -> Trigger {
+        Trigger::new(((3, 2), (1, 1)), event, TriggerAction::EndLevel)
+    }
+
+    #[test]
+    fn corners_are_normalized() {
+        let trigger = zone(TriggerEvent::Enter);
+        assert_eq!(trigger.area, ((1, 1), (3, 2)));
+        assert!(trigger.contains((3, 1)) && !trigger.contains((4, 1)));
+        assert_eq!(trigger.center(), Vector2::new(2.5, 2.0));
+    }
+
+    #[test]
+    fn enter_and_exit_fire_once_per_crossing() {
+        let mut enter = zone(TriggerEvent::Enter);
+        let mut exit = zone(TriggerEvent::Exit);
+        let steps = [(0, 0), (1, 1), (2, 1), (5, 5), (2, 2)];
+        let fired: Vec<(bool, bool)> = steps
+            .into_iter()
+            .map(|cell| (enter.update(Some(cell)), exit.update(Some(cell))))
+            .collect();
+        let expected = [
+            (false, false),
+            (true, false),
+            (false, false),
+            (false, true),
+            (true, false),
+        ];
+        assert_eq!(fired, expected);
+    }
+
+    #[test]
+    fn use_needs_the_player_inside() {
+        let mut trigger = zone(TriggerEvent::Use);
+        assert!(!trigger.update(Some((2, 2))));
+        assert!(trigger.is_usable());
+        trigger.update(None);
+        assert!(!trigger.is_usable());
+    }
+
+    #[test]
+    fn settled_zones_do_not_refire() {
+        let mut enter = zone(TriggerEvent::Enter);
+        enter.settle(Some((2, 2)));
+        assert!(!enter.update(Some((2, 2))));
+
+        let mut exit = zone(TriggerEvent::Exit);
+        exit.settle(Some((2, 2)));
+        assert!(exit.update(Some((5, 5))));
+    }
+}
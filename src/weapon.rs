@@ -0,0 +1,178 @@
+use raylib::prelude::*;
+use crate::animation::Animation;
+use crate::framebuffer::Framebuffer;
+use crate::textures::{TextureImage, TextureSet};
+
+pub const START_AMMO: u32 = 12;
+pub const AMMO_PICKUP: u32 = 8;
+pub const DAMAGE: f32 = 20.0;
+const FIRE_TIME: f32 = 0.4; // Segundos entre disparos
+const MUZZLE_TIME: f32 = 0.08; // Segundos que dura el fogonazo
+const MUZZLE_STRENGTH: f32 = 0.15;
+const VIEW_SIZE: f32 = 0.5; // Alto del arma en pantalla, en fracción del cuadro
+const IDLE_FRAME: &str = "gun_idle";
+const FIRE_FRAMES: [&str; 2] = ["gun_fire", "gun_recoil"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shot {
+    Fired,
+    Empty,
+}
+
+// Arma en mano del jugador, dibujada en primera persona al pie del cuadro
+pub struct Weapon {
+    pub ammo: u32,
+    cooldown: f32,                      // Tiempo hasta poder volver a disparar
+    animation: Animation<&'static str>, // Fogonazo y retroceso mientras dura el cooldown
+    muzzle_timer: f32,
+}
+
+impl Default for Weapon {
+    fn default() -> Self {
+        Weapon {
+            ammo: START_AMMO,
+            cooldown: 0.0,
+            animation: Animation::new(FIRE_FRAMES.to_vec(), FIRE_TIME / 2.0),
+            muzzle_timer: 0.0,
+        }
+    }
+}
+
+impl Weapon {
+    pub fn update(&mut self, dt: f32) {
+        if self.cooldown > 0.0 {
+            self.animation.update(dt);
+        }
+        self.cooldown = (self.cooldown - dt).max(0.0);
+        self.muzzle_timer = (self.muzzle_timer - dt).max(0.0);
+    }
+
+    // None mientras se recarga; sin balas no gasta nada pero avisa
+    pub fn fire(&mut self) -> Option<Shot> {
+        if self.cooldown > 0.0 {
+            return None;
+        }
+        if self.ammo == 0 {
+            return Some(Shot::Empty);
+        }
+        self.ammo -= 1;
+        self.cooldown = FIRE_TIME;
+        self.muzzle_timer = MUZZLE_TIME;
+        self.animation = Animation::new(FIRE_FRAMES.to_vec(), FIRE_TIME / 2.0);
+        Some(Shot::Fired)
+    }
+
+    // Encima de todo lo demás; el fogonazo ilumina un poco el cuadro entero
+    pub fn draw(&self, textures: &TextureSet, framebuffer: &mut Framebuffer) {
+        if self.muzzle_timer > 0.0 {
+            let amount = self.muzzle_timer / MUZZLE_TIME * MUZZLE_STRENGTH;
+            framebuffer.flash(Color::YELLOW, amount);
+        }
+
+        let name = if self.cooldown > 0.0 {
+            self.animation.current()
+        } else {
+            &IDLE_FRAME
+        };
+        let Some(image) = textures.sprite(name) else { return };
+        let (width, height) = (framebuffer.width(), framebuffer.height());
+        let size = (height as f32 * VIEW_SIZE) as usize;
+        let left = (width - size.min(width)) / 2;
+        let top = height - size;
+        for y in 0..size {
+            let ty = (y * image.height as usize / size) as u32;
+            for x in 0..size {
+                let tx = (x * image.width as usize / size) as u32;
+                let color = image.get_pixel_color(tx, ty);
+                if color.a > 127 {
+                    framebuffer.set(left + x, top + y, color);
+                }
+            }
+        }
+    }
+}
+
+// Disparo instantáneo: el blanco más cercano que cruza el rayo desde `origin`
+// hacia `angle` antes de `max_distance` (la pared del centro de la pantalla).
+// Cada blanco es un círculo visto desde arriba: (centro, radio).
+pub fn hitscan(
+    origin: Vector2,
+    angle: f32,
+    max_distance: f32,
+    targets: &[(Vector2, f32)],
+) -> Option<usize> {
+    let direction = Vector2::new(angle.cos(), angle.sin());
+    targets
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &(center, radius))| {
+            let offset = center - origin;
+            let along = offset.dot(direction);
+            let across = offset.x * direction.y - offset.y * direction.x;
+            (along > 0.0 && along < max_distance && across.abs() < radius).then_some((i, along))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+// Arma quieta y los dos cuadros del disparo
+pub fn load_textures(set: &mut TextureSet) {
+    let [fire, recoil] = FIRE_FRAMES;
+    set.insert_sprite(IDLE_FRAME, gun_image(0.0, false));
+    set.insert_sprite(fire, gun_image(6.0, true));
+    set.insert_sprite(recoil, gun_image(3.0, false));
+}
+
+// Pistola en primera persona vista desde atrás, con la mano; `recoil` la baja
+// unos pixeles y `muzzle` agrega el fogonazo en la boca del cañón
+fn gun_image(recoil: f32, muzzle: bool) -> TextureImage {
+    TextureImage::generate(64, |x, y| {
+        let (x, y) = (x as f32 + 0.5, y as f32 + 0.5 - recoil);
+        let flash = ((x - 32.0).powi(2) + (y - 14.0).powi(2)).sqrt();
+        let barrel = (27.0..37.0).contains(&x) && y >= 22.0;
+        let sight = (30.0..34.0).contains(&x) && (19.0..22.0).contains(&y);
+        let hand = ((x - 32.0) / 16.0).powi(2) + ((y - 60.0) / 18.0).powi(2) < 1.0;
+        if muzzle && flash < 5.0 {
+            Color::WHITE
+        } else if muzzle && flash < 10.0 {
+            Color::YELLOW
+        } else if barrel && x < 29.0 {
+            Color::new(90, 90, 100, 255)
+        } else if barrel || sight {
+            Color::new(50, 50, 60, 255)
+        } else if hand {
+            Color::new(200, 160, 120, 255)
+        } else {
+            Color::new(0, 0, 0, 0)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EAST: f32 = 0.0;
+
+    #[test]
+    fn hits_the_nearest_target_on_the_ray() {
+        let origin = Vector2::new(1.5, 1.5);
+        let targets = [
+            (Vector2::new(5.5, 1.5), 0.3),
+            (Vector2::new(3.5, 1.6), 0.3),
+            (Vector2::new(2.5, 2.5), 0.3), // Fuera del rayo
+            (Vector2::new(0.5, 1.5), 0.3), // Detrás del jugador
+        ];
+        assert_eq!(hitscan(origin, EAST, 10.0, &targets), Some(1));
+        assert_eq!(hitscan(origin, EAST, 10.0, &targets[2..]), None);
+    }
+
+    #[test]
+    fn a_wall_in_front_stops_the_shot() {
+        let origin = Vector2::new(1.5, 1.5);
+        let targets = [(Vector2::new(4.5, 1.5), 0.3)];
+        assert_eq!(hitscan(origin, EAST, 3.5, &targets), Some(0));
+        // La pared del centro queda antes que el blanco
+        assert_eq!(hitscan(origin, EAST, 2.5, &targets), None);
+    }
+}
@@ -0,0 +1,493 @@
+use raylib::prelude::*;
+use crate::animation::Animation;
+use crate::collision;
+use crate::door::{self, Doors};
+use crate::enemy::{self, Enemy, EnemyKind};
+use crate::input::InputState;
+use crate::item::{self, Item};
+use crate::level::Level;
+use crate::maze::{self, Maze};
+use crate::pathfinding::Cell;
+use crate::player::{self, Player};
+use crate::renderer::Camera;
+use crate::sprite::Sprite;
+use crate::textures::TextureSet;
+use crate::trigger::{Trigger, TriggerAction, TriggerEvent};
+use crate::weapon;
+
+const PLAYER: usize = 0; // El cuerpo del jugador es siempre la primera entidad
+
+// Paredes de siempre más todo lo que dibujan las entidades, puertas y casillas que dañan
+pub fn load_textures() -> Result<TextureSet, String> {
+    let mut set = TextureSet::load_default()?;
+    door::load_textures(&mut set);
+    maze::load_textures(&mut set);
+    item::load_textures(&mut set)?;
+    enemy::load_textures(&mut set);
+    weapon::load_textures(&mut set);
+    Ok(set)
+}
+
+// Posición y movimiento de una entidad
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Transform {
+    pub pos: Vector2,
+    pub angle: f32,        // Hacia dónde mira, en radianes
+    pub velocity: Vector2, // Celdas por segundo
+}
+
+impl Transform {
+    pub fn new(pos: Vector2, angle: f32) -> Self {
+        Transform {
+            pos,
+            angle,
+            velocity: Vector2::zero(),
+        }
+    }
+
+    pub fn cell(&self) -> Option<Cell> {
+        let (x, y) = (self.pos.x, self.pos.y);
+        (x >= 0.0 && y >= 0.0).then_some((x as usize, y as usize))
+    }
+}
+
+// Círculo que choca con las paredes y las puertas cerradas
+#[derive(Debug, Clone, Copy)]
+pub struct Collider {
+    pub radius: f32, // Celdas
+}
+
+// Cualquier cosa del nivel que no es pared ni puerta; cada componente es opcional
+#[derive(Default)]
+pub struct Entity {
+    pub transform: Transform,
+    pub sprite: Option<Sprite>,
+    pub collider: Option<Collider>,
+    pub ai: Option<Enemy>,
+    pub pickup: Option<Item>,
+    pub trigger: Option<Trigger>,
+}
+
+impl Entity {
+    // Objeto en el piso, esperando que el jugador pase por encima
+    pub fn pickup(pos: Vector2, item: Item) -> Self {
+        Entity {
+            transform: Transform::new(pos, 0.0),
+            sprite: Some(item.sprite()),
+            pickup: Some(item),
+            ..Default::default()
+        }
+    }
+
+    // `route` empieza en la celda donde aparece
+    pub fn enemy(kind: EnemyKind, route: &[Cell]) -> Self {
+        let (x, y) = route.first().copied().unwrap_or_default();
+        Entity {
+            transform: Transform::new(Vector2::new(x as f32 + 0.5, y as f32 + 0.5), 0.0),
+            sprite: Some(kind.sprite(false)),
+            collider: Some(Collider {
+                radius: enemy::RADIUS,
+            }),
+            ai: Some(Enemy::new(kind, route)),
+            ..Default::default()
+        }
+    }
+
+    // Estrella animada sobre una celda de meta: al pisarla termina el nivel
+    pub fn goal(cell: Cell) -> Self {
+        let trigger = Trigger::new((cell, cell), TriggerEvent::Enter, TriggerAction::EndLevel);
+        Entity {
+            sprite: Some(Sprite::new(
+                0.5,
+                Animation::new(vec!["star1", "star2", "star3"], 0.2),
+            )),
+            ..Entity::trigger(trigger)
+        }
+    }
+
+    // Zona invisible de un @trigger
+    pub fn trigger(trigger: Trigger) -> Self {
+        Entity {
+            transform: Transform::new(trigger.center(), 0.0),
+            trigger: Some(trigger),
+            ..Default::default()
+        }
+    }
+}
+
+// Todas las entidades de un nivel en juego, más el estado del jugador. Los sistemas
+// recorren las entidades que tienen los componentes que les importan.
+pub struct World {
+    pub player: Player,
+    entities: Vec<Entity>,
+}
+
+impl World {
+    // Jugador en la salida, estrellas en las metas, disparadores, objetos y enemigos
+    pub fn from_level(level: &Level) -> Self {
+        let body = Entity {
+            transform: Transform::new(level.spawn, level.spawn_angle),
+            collider: Some(Collider {
+                radius: player::RADIUS,
+            }),
+            ..Default::default()
+        };
+        let mut world = World {
+            player: Player::default(),
+            entities: vec![body],
+        };
+        for &cell in level.maze.goals() {
+            world.spawn(Entity::goal(cell));
+        }
+        for trigger in &level.triggers {
+            world.spawn(Entity::trigger(trigger.clone()));
+        }
+        for &(pos, item) in &level.items {
+            world.spawn(Entity::pickup(pos, item));
+        }
+        for (kind, route) in &level.enemies {
+            world.spawn(Entity::enemy(*kind, route));
+        }
+        world
+    }
+
+    pub fn spawn(&mut self, entity: Entity) {
+        self.entities.push(entity);
+    }
+
+    // Quita las entidades para las que `keep` da false; el jugador siempre queda
+    pub fn retain(&mut self, mut keep: impl FnMut(&Entity) -> bool) {
+        let mut index = 0;
+        self.entities.retain(|entity| {
+            let kept = index == PLAYER || keep(entity);
+            index += 1;
+            kept
+        });
+    }
+
+    pub fn entities_mut(&mut self) -> &mut [Entity] {
+        &mut self.entities
+    }
+
+    pub fn player_body(&self) -> &Transform {
+        &self.entities[PLAYER].transform
+    }
+
+    pub fn player_body_mut(&mut self) -> &mut Transform {
+        &mut self.entities[PLAYER].transform
+    }
+
+    pub fn camera(&self) -> Camera {
+        let body = self.player_body();
+        Camera {
+            pos: body.pos,
+            angle: body.angle,
+            fov: self.player.fov,
+        }
+    }
+
+    // Cuerpos que ocupan lugar (el jugador y los enemigos en pie): mantienen abiertas
+    // las puertas. Los caídos quedan en el piso sin trabarlas.
+    pub fn colliders(&self) -> Vec<(Vector2, f32)> {
+        self.entities
+            .iter()
+            .filter(|entity| !entity.ai.as_ref().is_some_and(Enemy::is_dead))
+            .filter_map(|entity| Some((entity.transform.pos, entity.collider?.radius)))
+            .collect()
+    }
+
+    // Enemigos en el orden del nivel, vivos o no
+    pub fn enemies(&self) -> impl Iterator<Item = (&Transform, &Enemy)> {
+        self.entities
+            .iter()
+            .filter_map(|entity| Some((&entity.transform, entity.ai.as_ref()?)))
+    }
+
+    // Objetos que siguen en el piso
+    pub fn pickups(&self) -> impl Iterator<Item = (Vector2, Item)> + '_ {
+        self.entities
+            .iter()
+            .filter_map(|entity| Some((entity.transform.pos, entity.pickup?)))
+    }
+
+    // Billboards con su posición, para el renderer
+    pub fn sprites(&self) -> Vec<(Vector2, &Sprite)> {
+        self.entities
+            .iter()
+            .filter_map(|entity| Some((entity.transform.pos, entity.sprite.as_ref()?)))
+            .collect()
+    }
+
+    // Sistema de animación
+    pub fn animate(&mut self, dt: f32) {
+        for sprite in self.entities.iter_mut().filter_map(|e| e.sprite.as_mut()) {
+            sprite.update(dt);
+        }
+    }
+
+    // El mouse gira al jugador una vez por cuadro
+    pub fn look(&mut self, input: &InputState) {
+        let body = &mut self.entities[PLAYER].transform;
+        self.player.look(input, body);
+    }
+
+    // Sistema de control: el jugador según la entrada
+    pub fn control(&mut self, input: &InputState, dt: f32) {
+        let body = &mut self.entities[PLAYER].transform;
+        self.player.update(input, body, dt);
+    }
+
+    // Sistema de IA: cada enemigo decide hacia dónde ir. Devuelve los golpes que
+    // recibió el jugador y de quién.
+    pub fn think(&mut self, maze: &Maze, doors: &Doors, dt: f32) -> Vec<(EnemyKind, f32)> {
+        let target = self.player_body().pos;
+        let mut hits = Vec::new();
+        for entity in &mut self.entities {
+            let Entity {
+                transform,
+                ai: Some(ai),
+                ..
+            } = entity
+            else {
+                continue;
+            };
+            if let Some(amount) = ai.update(transform, maze, doors, target, dt) {
+                hits.push((ai.kind, amount));
+            }
+        }
+        hits
+    }
+
+    // Sistema de colisiones: mueve cada cuerpo según su velocidad deslizándolo por
+    // las paredes; contra una pared se pierde solo la parte de la velocidad que
+    // apunta hacia ella
+    pub fn move_bodies(&mut self, maze: &Maze, doors: &Doors, dt: f32) {
+        for entity in &mut self.entities {
+            let Some(collider) = entity.collider else { continue };
+            let body = &mut entity.transform;
+            let motion = body.velocity * dt;
+            let sweep = collision::slide(maze, doors, body.pos, collider.radius, motion);
+            body.pos = sweep.pos;
+            for normal in sweep.normals {
+                let into_wall = body.velocity.dot(normal);
+                if into_wall < 0.0 {
+                    body.velocity -= normal * into_wall;
+                }
+            }
+        }
+    }
+
+    // Sistema de objetos: saca los que toca el jugador y los devuelve
+    pub fn collect_pickups(&mut self) -> Vec<Item> {
+        let pos = self.player_body().pos;
+        let mut collected = Vec::new();
+        self.retain(|entity| match entity.pickup {
+            Some(item) if entity.transform.pos.distance_to(pos) < item::PICKUP_RADIUS => {
+                collected.push(item);
+                false
+            }
+            _ => true,
+        });
+        collected
+    }
+
+    // Sistema de disparadores: compara la celda del jugador con cada zona y devuelve
+    // las acciones de las zonas de las que entró o salió en este paso
+    pub fn step_triggers(&mut self) -> Vec<TriggerAction> {
+        let cell = self.player_body().cell();
+        self.entities
+            .iter_mut()
+            .filter_map(|entity| entity.trigger.as_mut())
+            .filter_map(|trigger| trigger.update(cell).then(|| trigger.action.clone()))
+            .collect()
+    }
+
+    // Al cargar una partida: las zonas donde ya está el jugador no vuelven a
+    // dispararse al entrar, y las de salida esperan a que se vaya
+    pub fn settle_triggers(&mut self) {
+        let cell = self.player_body().cell();
+        for trigger in self.entities.iter_mut().filter_map(|e| e.trigger.as_mut()) {
+            trigger.settle(cell);
+        }
+    }
+
+    // Acciones de las zonas "use" donde está parado el jugador
+    pub fn use_triggers(&self) -> Vec<TriggerAction> {
+        self.entities
+            .iter()
+            .filter_map(|entity| entity.trigger.as_ref())
+            .filter(|trigger| trigger.is_usable())
+            .map(|trigger| trigger.action.clone())
+            .collect()
+    }
+
+    // Disparo del jugador por el centro de la pantalla: le da al primer enemigo en
+    // pie a menos de `range` (la pared de enfrente). Devuelve el tipo si lo derribó.
+    pub fn shoot(&mut self, range: f32, damage: f32) -> Option<EnemyKind> {
+        let body = *self.player_body();
+        let standing: Vec<usize> = (0..self.entities.len())
+            .filter(|&i| self.entities[i].ai.as_ref().is_some_and(|ai| !ai.is_dead()))
+            .collect();
+        let targets: Vec<(Vector2, f32)> = standing
+            .iter()
+            .map(|&i| (self.entities[i].transform.pos, enemy::HIT_RADIUS))
+            .collect();
+        let hit = weapon::hitscan(body.pos, body.angle, range, &targets)?;
+        let entity = &mut self.entities[standing[hit]];
+        let ai = entity.ai.as_mut()?;
+        if !ai.hurt(damage, body.pos) {
+            return None;
+        }
+        entity.sprite = Some(ai.kind.sprite(true));
+        Some(ai.kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::door::DoorState;
+    use crate::item::KeyColor;
+    use crate::stats::Stats;
+
+    // Un guardia parado en la puerta entre dos salas
+    const DOORWAY: &str = "\
+@enemy 2 2 guard
+#####
+#P  #
+##D##
+#  G#
+#####
+";
+
+    #[test]
+    fn a_corpse_in_a_doorway_lets_the_door_close() {
+        let level = Level::parse(DOORWAY).unwrap();
+        let mut world = World::from_level(&level);
+        let mut doors = Doors::new(&level.maze, &level.locks);
+        doors.open((2, 2));
+        let door = |doors: &Doors| doors.get((2, 2)).unwrap().state;
+
+        // En pie, el guardia traba la puerta abierta
+        for _ in 0..200 {
+            doors.update(0.05, &world.colliders());
+        }
+        assert_eq!(door(&doors), DoorState::Open);
+
+        // Derribado desde arriba, queda en la puerta pero ya no la traba
+        *world.player_body_mut() = Transform::new(Vector2::new(2.5, 1.5), PI as f32 / 2.0);
+        assert_eq!(world.shoot(5.0, 1000.0), Some(EnemyKind::Guard));
+        assert_eq!(world.colliders().len(), 1);
+        for _ in 0..200 {
+            doors.update(0.05, &world.colliders());
+        }
+        assert_eq!(door(&doors), DoorState::Closed);
+    }
+
+    // Dos guardias en fila a lo largo de un pasillo
+    const CORRIDOR: &str = "\
+@enemy 3 1 guard
+@enemy 5 1 guard
+########
+#P    G#
+########
+";
+
+    #[test]
+    fn shots_go_through_corpses() {
+        let level = Level::parse(CORRIDOR).unwrap();
+        let mut world = World::from_level(&level);
+        let guards = |world: &World| -> Vec<(f32, bool)> {
+            world
+                .enemies()
+                .map(|(body, ai)| (body.pos.x, ai.is_dead()))
+                .collect()
+        };
+
+        assert_eq!(world.shoot(10.0, 1000.0), Some(EnemyKind::Guard));
+        assert_eq!(guards(&world), [(3.5, true), (5.5, false)]);
+        assert_eq!(world.shoot(10.0, 1000.0), Some(EnemyKind::Guard));
+        assert_eq!(guards(&world), [(3.5, true), (5.5, true)]);
+        assert_eq!(world.shoot(10.0, 1000.0), None);
+    }
+
+    const PICKUPS: &str = "\
+@key 2 1 red
+@coin 3 1
+@health 3 1
+######
+#P  G#
+######
+";
+
+    #[test]
+    fn pickups_reach_the_inventory_and_the_stats() {
+        let level = Level::parse(PICKUPS).unwrap();
+        let mut world = World::from_level(&level);
+        let mut stats = Stats::new(&level);
+        world.player.health = 50.0;
+        assert_eq!(world.collect_pickups(), []);
+
+        // Lo mismo que hace la escena con cada objeto recogido
+        for x in [2.5, 3.5] {
+            world.player_body_mut().pos = Vector2::new(x, 1.5);
+            for item in world.collect_pickups() {
+                world.player.pick_up(item);
+                stats.collect(item);
+            }
+        }
+        assert_eq!(world.pickups().count(), 0);
+        assert_eq!(world.collect_pickups(), []);
+        assert!(world.player.inventory.has_key(KeyColor::Red));
+        assert_eq!(world.player.inventory.items(), [Item::Key(KeyColor::Red)]);
+        assert_eq!(world.player.health, 75.0);
+        assert_eq!(stats.score, 10);
+        assert_eq!(stats.collected, 1);
+    }
+
+    #[test]
+    fn retain_never_drops_the_player() {
+        let level = Level::parse(PICKUPS).unwrap();
+        let mut world = World::from_level(&level);
+        world.retain(|entity| entity.pickup.is_none());
+        assert_eq!(world.pickups().count(), 0);
+        assert_eq!(world.entities_mut().len(), 2); // El jugador y la meta
+
+        world.retain(|_| false);
+        assert_eq!(world.entities_mut().len(), 1);
+        assert_eq!(world.player_body().pos, level.spawn);
+        assert_eq!(world.colliders(), [(level.spawn, player::RADIUS)]);
+    }
+
+    // Un guardia al frente y un sabueso a la vuelta de la esquina
+    const CORNER: &str = "\
+@enemy 3 1 guard
+@enemy 1 3 hound
+######
+#P   #
+# ####
+#  G #
+######
+";
+
+    #[test]
+    fn shoot_hurts_only_the_enemy_in_sight() {
+        let level = Level::parse(CORNER).unwrap();
+        let mut world = World::from_level(&level);
+        // Salud que perdió el enemigo de cada tipo
+        let lost = |world: &World, kind: EnemyKind| {
+            let mut enemies = world.enemies();
+            kind.health() - enemies.find(|(_, ai)| ai.kind == kind).unwrap().1.health
+        };
+
+        assert_eq!(world.shoot(10.0, 5.0), None);
+        assert_eq!(lost(&world, EnemyKind::Guard), 5.0);
+        assert_eq!(lost(&world, EnemyKind::Hound), 0.0);
+
+        // Mirando hacia abajo el guardia queda fuera del rayo
+        world.player_body_mut().angle = PI as f32 / 2.0;
+        assert_eq!(world.shoot(10.0, 5.0), None);
+        assert_eq!(lost(&world, EnemyKind::Guard), 5.0);
+        assert_eq!(lost(&world, EnemyKind::Hound), 5.0);
+    }
+}